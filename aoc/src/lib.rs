@@ -0,0 +1,163 @@
+pub mod xorshift;
+
+use std::{
+    fmt,
+    fs::{self, File},
+    io::{self, BufRead, Read},
+    path::PathBuf,
+};
+
+/// Where a day reads its input from.
+#[derive(Debug, PartialEq)]
+pub enum Input {
+    File(PathBuf),
+    Stdin,
+}
+
+impl fmt::Display for Input {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Input::File(path) => write!(f, "{}", path.display()),
+            Input::Stdin => write!(f, "stdin"),
+        }
+    }
+}
+
+/// Why the input can't be picked from the command line arguments.
+#[derive(Debug, PartialEq)]
+pub enum ArgsError {
+    /// There's no path after `--input`.
+    MissingPath,
+}
+
+impl fmt::Display for ArgsError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ArgsError::MissingPath => write!(f, "--input needs a path, or - for stdin"),
+        }
+    }
+}
+
+impl Input {
+    pub fn puzzle() -> Input {
+        Input::File(PathBuf::from("./puzzle.input"))
+    }
+
+    /// The n-th example of the day, where the first one is `example.input` and the ones after
+    /// it are `example2.input`, `example3.input` and so on.
+    pub fn example(n: usize) -> Input {
+        match n {
+            0 | 1 => Input::File(PathBuf::from("./example.input")),
+            n => Input::File(PathBuf::from(format!("./example{}.input", n))),
+        }
+    }
+
+    /// Picks the input from the command line arguments, which is either
+    /// - the file after `--input`, or stdin when that is `-`,
+    /// - the example after `--example`, or the first one when no number follows it,
+    /// - stdin for a `-` on its own,
+    /// - or the puzzle input when none of these are given.
+    pub fn from_args(args: &[String]) -> Result<Input, ArgsError> {
+        for (i, arg) in args.iter().enumerate() {
+            match arg.as_str() {
+                "--input" => {
+                    let path = args.get(i + 1).ok_or(ArgsError::MissingPath)?;
+
+                    return Ok(match path.as_str() {
+                        "-" => Input::Stdin,
+                        _ => Input::File(PathBuf::from(path)),
+                    });
+                }
+                "--example" => {
+                    let n = args.get(i + 1).and_then(|n| n.parse().ok()).unwrap_or(1);
+
+                    return Ok(Input::example(n));
+                }
+                "-" => return Ok(Input::Stdin),
+                _ => (),
+            }
+        }
+
+        Ok(Input::puzzle())
+    }
+
+    pub fn open(&self) -> io::Result<Box<dyn BufRead>> {
+        match self {
+            Input::File(path) => Ok(Box::new(io::BufReader::new(File::open(path)?))),
+            Input::Stdin => Ok(Box::new(io::stdin().lock())),
+        }
+    }
+
+    /// The lines of the input, read as they're needed.
+    pub fn lines(&self) -> io::Result<io::Lines<Box<dyn BufRead>>> {
+        Ok(self.open()?.lines())
+    }
+
+    pub fn read_lines(&self) -> io::Result<Vec<String>> {
+        self.lines()?.collect()
+    }
+
+    pub fn read_to_string(&self) -> io::Result<String> {
+        match self {
+            Input::File(path) => fs::read_to_string(path),
+            Input::Stdin => {
+                let mut contents = String::new();
+                io::stdin().read_to_string(&mut contents)?;
+                Ok(contents)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn input_of(args: &[&str]) -> Input {
+        let args: Vec<String> = args.iter().map(|a| String::from(*a)).collect();
+
+        Input::from_args(&args).unwrap()
+    }
+
+    #[test]
+    fn test_from_args() {
+        assert_eq!(input_of(&["day"]), Input::puzzle());
+        assert_eq!(
+            input_of(&["day", "--bench", "10", "--input", "other.input"]),
+            Input::File(PathBuf::from("other.input"))
+        );
+        assert_eq!(input_of(&["day", "--input", "-"]), Input::Stdin);
+        assert_eq!(input_of(&["day", "--trace", "-"]), Input::Stdin);
+        assert_eq!(
+            Input::from_args(&[String::from("day"), String::from("--input")]),
+            Err(ArgsError::MissingPath)
+        );
+    }
+
+    #[test]
+    fn test_examples_from_args() {
+        assert_eq!(input_of(&["day", "--example"]), Input::example(1));
+        assert_eq!(
+            input_of(&["day", "--example", "--trace"]),
+            Input::File(PathBuf::from("./example.input"))
+        );
+        assert_eq!(
+            input_of(&["day", "--example", "2"]),
+            Input::File(PathBuf::from("./example2.input"))
+        );
+    }
+
+    #[test]
+    fn test_read_lines() {
+        let path = std::env::temp_dir().join("aoc-test-read-lines.input");
+        fs::write(&path, "first\nsecond\n").unwrap();
+
+        let input = Input::File(path.clone());
+
+        assert_eq!(input.read_lines().unwrap(), vec!["first", "second"]);
+        assert_eq!(input.read_to_string().unwrap(), "first\nsecond\n");
+        assert_eq!(input.to_string(), path.display().to_string());
+
+        fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,52 @@
+/// The seed every generator starts from, so that generated inputs are the same on every run
+/// and benchmarks are comparable.
+const SEED: u64 = 0x2545_f491_4f6c_dd1d;
+
+/// A xorshift generator, which is plenty random for generating test and benchmark inputs.
+pub struct Xorshift {
+    state: u64,
+}
+
+impl Default for Xorshift {
+    fn default() -> Self {
+        Xorshift::new()
+    }
+}
+
+impl Xorshift {
+    pub fn new() -> Xorshift {
+        Xorshift { state: SEED }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 7;
+        self.state ^= self.state << 17;
+        self.state
+    }
+
+    /// A number in `0..bound`.
+    pub fn below(&mut self, bound: u64) -> u64 {
+        self.next_u64() % bound
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sequence_is_fixed() {
+        let mut first = Xorshift::new();
+        let mut second = Xorshift::default();
+
+        let numbers: Vec<u64> = (0..100).map(|_| first.below(10)).collect();
+
+        assert!(numbers.iter().all(|&n| n < 10));
+        assert!(numbers.iter().any(|&n| n != numbers[0]));
+        assert_eq!(
+            numbers,
+            (0..100).map(|_| second.below(10)).collect::<Vec<_>>()
+        );
+    }
+}
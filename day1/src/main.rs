@@ -0,0 +1,598 @@
+use std::{
+    collections::VecDeque,
+    env, fmt, fs,
+    io::{self, BufRead},
+    time::Instant,
+};
+
+use aoc::{xorshift::Xorshift, Input};
+
+/// The English number words, which are the vocabulary unless another one is given.
+const WORDS: [(&str, u8); 9] = [
+    ("one", 1),
+    ("two", 2),
+    ("three", 3),
+    ("four", 4),
+    ("five", 5),
+    ("six", 6),
+    ("seven", 7),
+    ("eight", 8),
+    ("nine", 9),
+];
+
+/// Why a vocabulary file couldn't be read, with the number of the offending line.
+#[derive(Debug, PartialEq)]
+enum VocabularyError {
+    /// The line has no `=`, or nothing before it.
+    Malformed(usize),
+    /// The value isn't one of the digits 0 to 9, which a calibration value is made of.
+    NotADigit(usize),
+}
+
+impl fmt::Display for VocabularyError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            VocabularyError::Malformed(line) => write!(f, "line {} isn't like word=value", line),
+            VocabularyError::NotADigit(line) => {
+                write!(f, "line {} has a value other than 0-9", line)
+            }
+        }
+    }
+}
+
+/// Parses a vocabulary with a `word=value` per line, like `twee=2`. Empty lines and lines
+/// starting with `#` are skipped.
+fn parse_vocabulary(contents: &str) -> Result<Vec<(String, u8)>, VocabularyError> {
+    let mut words: Vec<(String, u8)> = Vec::new();
+
+    for (i, line) in contents.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let (word, value) = line
+            .split_once('=')
+            .map(|(word, value)| (word.trim(), value))
+            .filter(|(word, _)| !word.is_empty())
+            .ok_or(VocabularyError::Malformed(i + 1))?;
+        let value: u8 = value
+            .trim()
+            .parse()
+            .ok()
+            .filter(|&v| v <= 9)
+            .ok_or(VocabularyError::NotADigit(i + 1))?;
+
+        words.push((String::from(word), value));
+    }
+
+    Ok(words)
+}
+
+fn default_words() -> Vec<(String, u8)> {
+    WORDS.iter().map(|&(w, v)| (String::from(w), v)).collect()
+}
+
+/// The tokens to look for: the digits 0 to 9 themselves and the words of the vocabulary.
+fn tokens_of(words: &[(String, u8)]) -> Vec<(String, u8)> {
+    let mut tokens: Vec<(String, u8)> = (0..=9).map(|d| (d.to_string(), d)).collect();
+    tokens.extend(words.iter().cloned());
+
+    tokens
+}
+
+/// An Aho-Corasick automaton over a set of tokens, which finds all of them in a line in a
+/// single left-to-right pass, including overlapping ones like the `two` and `one` in `twone`.
+struct Scanner {
+    /// Per state the next state for every byte, with the failure links already followed.
+    next: Vec<[usize; 256]>,
+    /// Per state the length and value of every token that ends in it.
+    outputs: Vec<Vec<(usize, u8)>>,
+}
+
+impl Scanner {
+    fn new(tokens: &[(String, u8)]) -> Scanner {
+        // build the trie, with 0 as the root and as "no transition yet"
+        let mut next: Vec<[usize; 256]> = vec![[0; 256]];
+        let mut outputs: Vec<Vec<(usize, u8)>> = vec![vec![]];
+
+        for (token, value) in tokens {
+            let mut state = 0;
+            for &b in token.as_bytes() {
+                if next[state][b as usize] == 0 {
+                    next.push([0; 256]);
+                    outputs.push(vec![]);
+                    next[state][b as usize] = next.len() - 1;
+                }
+                state = next[state][b as usize];
+            }
+            outputs[state].push((token.len(), *value));
+        }
+
+        // breadth first, so the failure state of a state is always done before the state itself
+        let mut fail = vec![0; next.len()];
+        let mut queue: VecDeque<usize> = next[0].iter().copied().filter(|&s| s != 0).collect();
+
+        while let Some(state) = queue.pop_front() {
+            let inherited = outputs[fail[state]].clone();
+            outputs[state].extend(inherited);
+
+            let fail_next = next[fail[state]];
+            for (b, child) in next[state].iter_mut().enumerate() {
+                if *child == 0 {
+                    // no token continues with this byte, so continue as the failure state would
+                    *child = fail_next[b];
+                } else {
+                    fail[*child] = fail_next[b];
+                    queue.push_back(*child);
+                }
+            }
+        }
+
+        Scanner { next, outputs }
+    }
+
+    /// The values of the first and the last token in the line, by where they start.
+    fn first_and_last(&self, line: &str) -> Option<(u8, u8)> {
+        let mut state = 0;
+        let mut first: Option<(usize, u8)> = None;
+        let mut last: Option<(usize, u8)> = None;
+
+        for (end, &b) in line.as_bytes().iter().enumerate() {
+            state = self.next[state][b as usize];
+
+            for &(len, value) in &self.outputs[state] {
+                let start = end + 1 - len;
+
+                match first {
+                    Some((s, _)) if s <= start => (),
+                    _ => first = Some((start, value)),
+                }
+                match last {
+                    Some((s, _)) if s >= start => (),
+                    _ => last = Some((start, value)),
+                }
+            }
+        }
+
+        Some((first?.1, last?.1))
+    }
+
+    /// Every token in the line, also overlapping ones, as its start, length and value, in the
+    /// order they start in.
+    fn matches(&self, line: &str) -> Vec<(usize, usize, u8)> {
+        let mut state = 0;
+        let mut matches: Vec<(usize, usize, u8)> = Vec::new();
+
+        for (end, &b) in line.as_bytes().iter().enumerate() {
+            state = self.next[state][b as usize];
+
+            for &(len, value) in &self.outputs[state] {
+                matches.push((end + 1 - len, len, value));
+            }
+        }
+        matches.sort_by_key(|&(start, len, _)| (start, len));
+
+        matches
+    }
+}
+
+/// Which tokens count as a digit of a calibration value.
+#[derive(PartialEq, Debug, Clone, Copy)]
+enum Mode {
+    DigitsOnly,
+    DigitsAndWords,
+}
+
+impl fmt::Display for Mode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Mode::DigitsOnly => write!(f, "digits only"),
+            Mode::DigitsAndWords => write!(f, "digits and words"),
+        }
+    }
+}
+
+/// Finds the calibration value of a line in either mode, with a scanner per mode.
+struct Decoder {
+    digits: Scanner,
+    digits_and_words: Scanner,
+}
+
+impl Decoder {
+    fn new(words: &[(String, u8)]) -> Decoder {
+        Decoder {
+            digits: Scanner::new(&tokens_of(&[])),
+            digits_and_words: Scanner::new(&tokens_of(words)),
+        }
+    }
+
+    fn scanner(&self, mode: Mode) -> &Scanner {
+        match mode {
+            Mode::DigitsOnly => &self.digits,
+            Mode::DigitsAndWords => &self.digits_and_words,
+        }
+    }
+
+    fn calibration_value(&self, line: &str, mode: Mode) -> Option<u32> {
+        let (first, last) = self.scanner(mode).first_and_last(line)?;
+
+        Some(u32::from(first) * 10 + u32::from(last))
+    }
+
+    /// Lists the tokens found in the line with the byte offset they start at, and the value
+    /// they make, like `two@1 one@3 3@6 four@7 => 24`.
+    fn explain(&self, line: &str, mode: Mode) -> String {
+        let tokens: Vec<String> = self
+            .scanner(mode)
+            .matches(line)
+            .iter()
+            .map(|&(start, len, _)| format!("{}@{}", &line[start..start + len], start))
+            .collect();
+
+        match self.calibration_value(line, mode) {
+            Some(value) => format!("{} => {}", tokens.join(" "), value),
+            None => String::from("no digit"),
+        }
+    }
+}
+
+/// The sum of the calibration values of all lines, and the numbers of the lines without any
+/// digit, which are left out of the sum.
+struct Calibration {
+    sum: u64,
+    lines_without_digit: Vec<usize>,
+}
+
+/// Sums the calibration values in every mode while reading, so the input never has to fit in
+/// memory. Returns a calibration per mode.
+fn calibrate(
+    reader: impl BufRead,
+    decoder: &Decoder,
+    modes: &[Mode],
+) -> io::Result<Vec<Calibration>> {
+    let mut calibrations: Vec<Calibration> = modes
+        .iter()
+        .map(|_| Calibration {
+            sum: 0,
+            lines_without_digit: vec![],
+        })
+        .collect();
+
+    for (i, line) in reader.lines().enumerate() {
+        let line = line?;
+
+        for (&mode, calibration) in modes.iter().zip(calibrations.iter_mut()) {
+            match decoder.calibration_value(&line, mode) {
+                Some(value) => calibration.sum += u64::from(value),
+                None => calibration.lines_without_digit.push(i + 1),
+            }
+        }
+    }
+
+    Ok(calibrations)
+}
+
+fn match_number(substr: &str, tokens: &[(String, u8)]) -> Option<u8> {
+    tokens
+        .iter()
+        .find(|(token, _)| substr.contains(token.as_str()))
+        .map(|&(_, value)| value)
+}
+
+/// The calibration value found by matching every prefix and suffix of the line, which is
+/// quadratic in the length of the line.
+fn calibration_value_sliced(line: &str, tokens: &[(String, u8)]) -> Option<u32> {
+    let first_num = (0..line.len()).find_map(|sub1| match_number(&line[0..sub1 + 1], tokens))?;
+    let second_num = (0..line.len())
+        .rev()
+        .find_map(|sub2| match_number(&line[sub2..line.len()], tokens))?;
+
+    Some(u32::from(first_num) * 10 + u32::from(second_num))
+}
+
+/// Lines of `len` random letters with a single word of the vocabulary in the middle, or a
+/// digit if it has no words, so that the sliced approach has to try about every prefix and
+/// suffix.
+fn generate_lines(words: &[(String, u8)], count: usize, len: usize) -> Vec<String> {
+    let mut rng = Xorshift::new();
+    let mut next = |bound: u64| rng.below(bound);
+    let tokens = tokens_of(words);
+    let inserted = if words.is_empty() { &tokens } else { words };
+
+    (0..count)
+        .map(|_| {
+            let mut line: String = (0..len).map(|_| (b'a' + next(26) as u8) as char).collect();
+            line.insert_str(len / 2, &inserted[next(inserted.len() as u64) as usize].0);
+            line
+        })
+        .collect()
+}
+
+fn run_benchmark(words: &[(String, u8)], len: usize) {
+    let lines = generate_lines(words, 100, len);
+    let tokens = &tokens_of(words);
+
+    let now = Instant::now();
+    let sum: u32 = lines
+        .iter()
+        .map(|l| calibration_value_sliced(l, tokens).unwrap())
+        .sum();
+    println!("Sliced:  {} in {:?}", sum, now.elapsed());
+
+    let now = Instant::now();
+    let decoder = Decoder::new(words);
+    let sum: u32 = lines
+        .iter()
+        .map(|l| decoder.calibration_value(l, Mode::DigitsAndWords).unwrap())
+        .sum();
+    println!("Scanner: {} in {:?}", sum, now.elapsed());
+}
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+
+    let words: Vec<(String, u8)> = match args.iter().position(|a| a == "--vocabulary") {
+        Some(i) => {
+            let path = args.get(i + 1).map_or("", |p| p.as_str());
+
+            let contents = match fs::read_to_string(path) {
+                Ok(contents) => contents,
+                Err(e) => {
+                    eprintln!("Can't read vocabulary {}, {}", path, e);
+                    return;
+                }
+            };
+
+            match parse_vocabulary(&contents) {
+                Ok(words) => words,
+                Err(e) => {
+                    eprintln!("Can't use vocabulary {}, {}", path, e);
+                    return;
+                }
+            }
+        }
+        None => default_words(),
+    };
+
+    if let Some(i) = args.iter().position(|a| a == "--bench") {
+        match args.get(i + 1).and_then(|len| len.parse().ok()) {
+            Some(len) => run_benchmark(&words, len),
+            None => eprintln!("Can't run the benchmark, --bench needs a line length"),
+        }
+        return;
+    }
+
+    let input = match Input::from_args(&args) {
+        Ok(input) => input,
+        Err(e) => {
+            eprintln!("Can't pick the input, {}", e);
+            return;
+        }
+    };
+
+    let decoder = Decoder::new(&words);
+    let modes = [Mode::DigitsOnly, Mode::DigitsAndWords];
+
+    if args.iter().any(|a| a == "--explain") {
+        let lines = match input.lines() {
+            Ok(lines) => lines,
+            Err(e) => {
+                eprintln!("Can't read {}, {}", input, e);
+                return;
+            }
+        };
+
+        for line in lines {
+            let line = line.unwrap();
+
+            println!("{}", line);
+            for mode in modes {
+                println!("  {}: {}", mode, decoder.explain(&line, mode));
+            }
+        }
+        return;
+    }
+
+    let now = Instant::now();
+
+    let calibrations = match input.open().and_then(|r| calibrate(r, &decoder, &modes)) {
+        Ok(calibrations) => calibrations,
+        Err(e) => {
+            eprintln!("Can't read {}, {}", input, e);
+            return;
+        }
+    };
+
+    println!("Duration: {}", now.elapsed().as_millis());
+
+    for (mode, calibration) in modes.iter().zip(&calibrations) {
+        for line in &calibration.lines_without_digit {
+            eprintln!("Line {} has no digit with {}, so it's left out", line, mode);
+        }
+
+        println!(
+            "The calibration result with {} is {}",
+            mode, calibration.sum
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tokens_from(words: &[(&str, u8)]) -> Vec<(String, u8)> {
+        words.iter().map(|&(w, v)| (String::from(w), v)).collect()
+    }
+
+    #[test]
+    fn test_examples() {
+        let decoder = Decoder::new(&default_words());
+        let modes = [Mode::DigitsOnly, Mode::DigitsAndWords];
+
+        let calibrations = calibrate(Input::example(1).open().unwrap(), &decoder, &modes).unwrap();
+
+        assert_eq!(calibrations[0].sum, 142);
+        assert_eq!(calibrations[1].sum, 142);
+        assert!(calibrations[0].lines_without_digit.is_empty());
+
+        let calibrations = calibrate(Input::example(2).open().unwrap(), &decoder, &modes).unwrap();
+
+        assert_eq!(calibrations[0].lines_without_digit, vec![2]);
+        assert_eq!(calibrations[1].sum, 29 + 83 + 13 + 24 + 42 + 14 + 76);
+        assert!(calibrations[1].lines_without_digit.is_empty());
+    }
+
+    #[test]
+    fn test_lines_without_digit() {
+        let decoder = Decoder::new(&default_words());
+        let input = "two1nine\nabc\n\nxtwone3four\nzero\n";
+
+        let calibrations = calibrate(input.as_bytes(), &decoder, &[Mode::DigitsAndWords]).unwrap();
+
+        assert_eq!(calibrations[0].sum, 29 + 24);
+        assert_eq!(calibrations[0].lines_without_digit, vec![2, 3, 5]);
+    }
+
+    #[test]
+    fn test_calibration_value_modes() {
+        let decoder = Decoder::new(&default_words());
+
+        assert_eq!(
+            decoder.calibration_value("xtwone3four", Mode::DigitsOnly),
+            Some(33)
+        );
+        assert_eq!(
+            decoder.calibration_value("xtwone3four", Mode::DigitsAndWords),
+            Some(24)
+        );
+        assert_eq!(decoder.calibration_value("eightwo", Mode::DigitsOnly), None);
+        assert_eq!(decoder.calibration_value("a0b5", Mode::DigitsOnly), Some(5));
+        assert_eq!(
+            decoder.calibration_value("a0btwo", Mode::DigitsAndWords),
+            Some(2)
+        );
+    }
+
+    #[test]
+    fn test_explain() {
+        let decoder = Decoder::new(&default_words());
+
+        assert_eq!(
+            decoder.explain("xtwone3four", Mode::DigitsAndWords),
+            "two@1 one@3 3@6 four@7 => 24"
+        );
+        assert_eq!(
+            decoder.explain("xtwone3four", Mode::DigitsOnly),
+            "3@6 => 33"
+        );
+        assert_eq!(decoder.explain("eightwo", Mode::DigitsOnly), "no digit");
+    }
+
+    #[test]
+    fn test_overlapping_words() {
+        let scanner = Scanner::new(&tokens_of(&default_words()));
+
+        assert_eq!(scanner.first_and_last("twone"), Some((2, 1)));
+        assert_eq!(scanner.first_and_last("eightwo"), Some((8, 2)));
+        assert_eq!(scanner.first_and_last("oneight"), Some((1, 8)));
+        assert_eq!(scanner.first_and_last("sevenine"), Some((7, 9)));
+        assert_eq!(scanner.first_and_last("abc"), None);
+    }
+
+    #[test]
+    fn test_tokens_inside_tokens() {
+        // the token that starts first wins, even when a token inside it ends earlier
+        let scanner = Scanner::new(&tokens_from(&[("abcd", 1), ("bc", 2), ("c", 3)]));
+
+        assert_eq!(scanner.first_and_last("xabcdx"), Some((1, 3)));
+        assert_eq!(scanner.first_and_last("xbcx"), Some((2, 3)));
+    }
+
+    #[test]
+    fn test_scanner_matches_sliced() {
+        let tokens = tokens_of(&default_words());
+        let decoder = Decoder::new(&default_words());
+
+        for line in generate_lines(&default_words(), 20, 50) {
+            assert_eq!(
+                decoder.calibration_value(&line, Mode::DigitsAndWords),
+                calibration_value_sliced(&line, &tokens),
+                "{}",
+                line
+            );
+        }
+        assert_eq!(calibration_value_sliced("abc", &tokens), None);
+    }
+
+    #[test]
+    fn test_generated_lines_use_the_vocabulary() {
+        let words = parse_vocabulary(&fs::read_to_string("./dutch.vocabulary").unwrap()).unwrap();
+        let tokens = tokens_of(&words);
+        let decoder = Decoder::new(&words);
+
+        for line in generate_lines(&words, 20, 50) {
+            assert!(
+                words.iter().any(|(w, _)| line.contains(w.as_str())),
+                "{}",
+                line
+            );
+            assert_eq!(
+                decoder.calibration_value(&line, Mode::DigitsAndWords),
+                calibration_value_sliced(&line, &tokens)
+            );
+        }
+        for line in generate_lines(&[], 20, 50) {
+            assert!(decoder.calibration_value(&line, Mode::DigitsOnly).is_some());
+        }
+    }
+
+    #[test]
+    fn test_match_number() {
+        let tokens = tokens_of(&default_words());
+
+        assert_eq!(match_number("xtwo", &tokens), Some(2));
+        assert_eq!(match_number("x0", &tokens), Some(0));
+        assert_eq!(match_number("xzero", &tokens), None);
+    }
+
+    #[test]
+    fn test_dutch_vocabulary() {
+        let words = parse_vocabulary(&fs::read_to_string("./dutch.vocabulary").unwrap()).unwrap();
+        let scanner = Scanner::new(&tokens_of(&words));
+
+        assert_eq!(words.len(), 10);
+        assert_eq!(scanner.first_and_last("xtweeenx"), Some((2, 1)));
+        assert_eq!(scanner.first_and_last("nulachtzeven"), Some((0, 7)));
+        assert_eq!(scanner.first_and_last("zevennul"), Some((7, 0)));
+        assert_eq!(scanner.first_and_last("achtzeven0"), Some((8, 0)));
+        assert_eq!(scanner.first_and_last("one 4 two"), Some((4, 4)));
+    }
+
+    #[test]
+    fn test_parse_vocabulary() {
+        assert_eq!(
+            parse_vocabulary("# custom\nuno=1\n\nX = 7\n"),
+            Ok(tokens_from(&[("uno", 1), ("X", 7)]))
+        );
+        assert_eq!(
+            parse_vocabulary("one=1\ntwo\n"),
+            Err(VocabularyError::Malformed(2))
+        );
+        assert_eq!(parse_vocabulary("=1"), Err(VocabularyError::Malformed(1)));
+        assert_eq!(parse_vocabulary(" =1"), Err(VocabularyError::Malformed(1)));
+        assert_eq!(
+            parse_vocabulary("zero=0\none=1"),
+            Ok(tokens_from(&[("zero", 0), ("one", 1)]))
+        );
+        assert_eq!(
+            parse_vocabulary("minus=-1"),
+            Err(VocabularyError::NotADigit(1))
+        );
+        assert_eq!(
+            parse_vocabulary("ten=10"),
+            Err(VocabularyError::NotADigit(1))
+        );
+    }
+}
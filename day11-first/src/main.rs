@@ -1,11 +1,11 @@
 use std::{
+    env,
     fmt,
-    fs::File,
-    io::{self, BufRead},
-    path::Path,
+    io,
     time::Instant,
 };
 
+use aoc::Input;
 use regex::Regex;
 
 struct Universe {
@@ -98,31 +98,22 @@ impl Universe {
     }
 }
 
-fn read_lines<P>(filename: P) -> io::Result<io::Lines<io::BufReader<File>>>
-where
-    P: AsRef<Path>,
-{
-    let file = File::open(filename)?;
-    Ok(io::BufReader::new(file).lines())
-}
-
-fn parse_input(path: &str) -> Universe {
+fn parse_input(input: &Input) -> io::Result<Universe> {
     let mut universe = Universe {
         map: vec![],
         galaxies: vec![],
     };
 
-    if let Ok(lines) = read_lines(path) {
-        let empty_reg = Regex::new(r"^\.+$").unwrap();
+    let lines = input.lines()?;
+    let empty_reg = Regex::new(r"^\.+$").unwrap();
 
-        for line_raw in lines {
-            let line = line_raw.unwrap();
+    for line_raw in lines {
+        let line = line_raw?;
 
+        universe.map.push(line.chars().collect());
+        if empty_reg.is_match(&line) {
+            // this is an empty line, so push it again to double it
             universe.map.push(line.chars().collect());
-            if empty_reg.is_match(&line) {
-                // this is an empty line, so push it again to double it
-                universe.map.push(line.chars().collect());
-            }
         }
     }
 
@@ -132,13 +123,27 @@ fn parse_input(path: &str) -> Universe {
 
     universe.store_galaxies();
 
-    universe
+    Ok(universe)
 }
 
 fn main() {
+    let args: Vec<String> = env::args().collect();
     let now = Instant::now();
 
-    let universe = parse_input("./puzzle.input");
+    let input = match Input::from_args(&args) {
+        Ok(input) => input,
+        Err(e) => {
+            eprintln!("Can't pick the input, {}", e);
+            return;
+        }
+    };
+    let universe = match parse_input(&input) {
+        Ok(universe) => universe,
+        Err(e) => {
+            eprintln!("Can't read {}, {}", input, e);
+            return;
+        }
+    };
 
     let sum = universe.get_sum_shortest_paths();
 
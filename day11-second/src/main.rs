@@ -1,12 +1,12 @@
 use std::{
+    env,
     fmt,
-    fs::File,
-    io::{self, BufRead},
-    path::Path,
+    io,
     time::Instant,
 };
 
 use arrayvec::ArrayVec;
+use aoc::Input;
 use regex::Regex;
 
 const EXPANSION_RATE: usize = 1_000_000;
@@ -156,31 +156,22 @@ impl Universe {
     }
 }
 
-fn read_lines<P>(filename: P) -> io::Result<io::Lines<io::BufReader<File>>>
-where
-    P: AsRef<Path>,
-{
-    let file = File::open(filename)?;
-    Ok(io::BufReader::new(file).lines())
-}
-
-fn parse_input(path: &str) -> Universe {
+fn parse_input(input: &Input) -> io::Result<Universe> {
     let mut universe = Universe {
         map: ArrayVec::new(),
         galaxies: vec![],
     };
 
-    if let Ok(lines) = read_lines(path) {
-        let empty_reg = Regex::new(r"^\.+$").unwrap();
+    let lines = input.lines()?;
+    let empty_reg = Regex::new(r"^\.+$").unwrap();
 
-        for line_raw in lines {
-            let line = line_raw.unwrap();
+    for line_raw in lines {
+        let line = line_raw?;
 
-            let empty = empty_reg.is_match(&line);
-            universe
-                .map
-                .push(line.chars().map(|l| SuperChar { char: l, empty }).collect());
-        }
+        let empty = empty_reg.is_match(&line);
+        universe
+            .map
+            .push(line.chars().map(|l| SuperChar { char: l, empty }).collect());
     }
 
     universe.transpose_map();
@@ -189,13 +180,27 @@ fn parse_input(path: &str) -> Universe {
 
     universe.store_galaxies();
 
-    universe
+    Ok(universe)
 }
 
 fn main() {
+    let args: Vec<String> = env::args().collect();
     let now = Instant::now();
 
-    let universe = parse_input("./puzzle.input");
+    let input = match Input::from_args(&args) {
+        Ok(input) => input,
+        Err(e) => {
+            eprintln!("Can't pick the input, {}", e);
+            return;
+        }
+    };
+    let universe = match parse_input(&input) {
+        Ok(universe) => universe,
+        Err(e) => {
+            eprintln!("Can't read {}, {}", input, e);
+            return;
+        }
+    };
 
     let sum = universe.get_sum_shortest_paths();
 
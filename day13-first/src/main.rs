@@ -1,9 +1,11 @@
 use std::{
-    fs::File,
-    io::{self, BufRead},
-    path::Path, time::Instant,
+    env,
+    io,
+    time::Instant,
 };
 
+use aoc::Input;
+
 const HORIZONTAL_REFLECTION_FACTOR: usize = 100;
 
 struct Pattern {
@@ -14,14 +16,6 @@ struct Pattern {
     ver_pairs: Vec<(usize, usize)>,
 }
 
-fn read_lines<P>(filename: P) -> io::Result<io::Lines<io::BufReader<File>>>
-where
-    P: AsRef<Path>,
-{
-    let file = File::open(filename)?;
-    Ok(io::BufReader::new(file).lines())
-}
-
 fn get_pattern_summary(pattern: &Pattern) -> usize {
     let check_pairs = |map: &Vec<String>, pairs: &Vec<(usize, usize)>| -> Option<usize> {
         'pair_loop: for pair in pairs {
@@ -43,7 +37,7 @@ fn get_pattern_summary(pattern: &Pattern) -> usize {
             return Some(pair.0)
         }
 
-        return None
+        None
     };
 
     if let Some(hor_pair) = check_pairs(&pattern.hor_map, &pattern.hor_pairs) {
@@ -57,83 +51,96 @@ fn get_pattern_summary(pattern: &Pattern) -> usize {
     panic!("This shouldn't happen. Pattern {} has no solution", pattern._num);
 }
 
-fn get_patterns(path: &str) -> Vec<Pattern> {
+fn get_patterns(input: &Input) -> io::Result<Vec<Pattern>> {
     let mut patterns: Vec<Pattern> = Vec::new();
 
-    if let Ok(lines) = read_lines(path) {
-        let mut hor_map: Vec<String> = Vec::new();
-        let mut hor_pairs: Vec<(usize, usize)> = Vec::new();
-        let mut ver_map: Vec<String> = Vec::new();
-        let mut ver_pairs: Vec<(usize, usize)> = Vec::new();
-
-        let mut prev_line = String::new();
-        let mut hor_index = 0;
-
-        for line_raw in lines {
-            let line = line_raw.unwrap();
-
-            if line.is_empty() {
-                // now that the verticals are done as well, check for their pairs
-                let mut it = ver_map.iter().enumerate().peekable();
-                
-                while let Some((i, s1)) = it.next() {
-                    if let Some((j, s2)) = it.peek() {
-                        if s1.eq(*s2) {
-                            ver_pairs.push((i,*j));
-                        }
+    let lines = input.lines()?;
+    let mut hor_map: Vec<String> = Vec::new();
+    let mut hor_pairs: Vec<(usize, usize)> = Vec::new();
+    let mut ver_map: Vec<String> = Vec::new();
+    let mut ver_pairs: Vec<(usize, usize)> = Vec::new();
+
+    let mut prev_line = String::new();
+    let mut hor_index = 0;
+
+    for line_raw in lines {
+        let line = line_raw?;
+
+        if line.is_empty() {
+            // now that the verticals are done as well, check for their pairs
+            let mut it = ver_map.iter().enumerate().peekable();
+            
+            while let Some((i, s1)) = it.next() {
+                if let Some((j, s2)) = it.peek() {
+                    if s1.eq(*s2) {
+                        ver_pairs.push((i,*j));
                     }
                 }
+            }
 
-                // done with this pattern, so push it
-                patterns.push(Pattern { _num: patterns.len(), hor_map, hor_pairs, ver_map, ver_pairs });
+            // done with this pattern, so push it
+            patterns.push(Pattern { _num: patterns.len(), hor_map, hor_pairs, ver_map, ver_pairs });
 
-                // and then reset all important variables
-                hor_map = Vec::new();
-                hor_pairs = Vec::new();
-                ver_map = Vec::new();
-                ver_pairs = Vec::new();
+            // and then reset all important variables
+            hor_map = Vec::new();
+            hor_pairs = Vec::new();
+            ver_map = Vec::new();
+            ver_pairs = Vec::new();
 
-                prev_line = String::new();
-                hor_index = 0;
+            prev_line = String::new();
+            hor_index = 0;
 
-                continue;
-            }
+            continue;
+        }
 
-            // process the horizontal line
+        // process the horizontal line
 
-            hor_map.push(line.clone());
+        hor_map.push(line.clone());
 
-            if line.eq(&prev_line) {
-                hor_pairs.push((hor_index-1, hor_index));
-            }
+        if line.eq(&prev_line) {
+            hor_pairs.push((hor_index-1, hor_index));
+        }
 
-            // process the line as vertical
+        // process the line as vertical
 
-            if ver_map.is_empty() {
-                // initialize with length of string
-                for _ in 0..line.len() {
-                    ver_map.push(String::new());
-                }
-            }
-
-            // push the current line as separate chars to each vertical vec String
-            for (c, s) in line.chars().zip(ver_map.iter_mut()) {
-                s.push(c);
+        if ver_map.is_empty() {
+            // initialize with length of string
+            for _ in 0..line.len() {
+                ver_map.push(String::new());
             }
+        }
 
-            // prepare for next it
-            hor_index += 1;
-            prev_line = line;
+        // push the current line as separate chars to each vertical vec String
+        for (c, s) in line.chars().zip(ver_map.iter_mut()) {
+            s.push(c);
         }
+
+        // prepare for next it
+        hor_index += 1;
+        prev_line = line;
     }
 
-    patterns
+    Ok(patterns)
 }
 
 fn main() {
+    let args: Vec<String> = env::args().collect();
     let now = Instant::now();
 
-    let patterns = get_patterns("./puzzle.input");
+    let input = match Input::from_args(&args) {
+        Ok(input) => input,
+        Err(e) => {
+            eprintln!("Can't pick the input, {}", e);
+            return;
+        }
+    };
+    let patterns = match get_patterns(&input) {
+        Ok(patterns) => patterns,
+        Err(e) => {
+            eprintln!("Can't read {}, {}", input, e);
+            return;
+        }
+    };
 
     let sum = patterns.iter().fold(0, |acc, pat| acc + get_pattern_summary(pat));
 
@@ -1,24 +1,21 @@
-use std::{
-    collections::HashMap,
-    fs::File,
-    io::{self, BufRead},
-    path::Path,
-    vec,
-};
+use std::{collections::HashMap, env, fmt, time::Instant, vec};
 
+use aoc::{xorshift::Xorshift, Input};
 use regex::Regex;
 
+/// A part's ratings, indexed the same as the attribute names parsed from the input.
 struct Part {
-    x: u32,
-    m: u32,
-    a: u32,
-    s: u32,
+    ratings: Vec<u32>,
 }
 
-#[derive(PartialEq, Debug)]
+#[derive(PartialEq, Debug, Clone, Copy)]
 enum Op {
     Lt,
     Gt,
+    Le,
+    Ge,
+    Eq,
+    Ne,
 }
 
 #[derive(PartialEq, Debug, Clone)]
@@ -35,21 +32,106 @@ struct Workflow {
 }
 
 struct Rule {
-    prop: char,
+    prop: usize,
     op: Op,
     value: u32,
     result: State,
 }
 
-fn read_lines<P>(filename: P) -> io::Result<io::Lines<io::BufReader<File>>>
-where
-    P: AsRef<Path>,
-{
-    let file = File::open(filename)?;
-    Ok(io::BufReader::new(file).lines())
+impl Op {
+    /// Whether `value` compares to the rule's `bound` as this operator demands.
+    fn holds(&self, value: u32, bound: u32) -> bool {
+        match self {
+            Op::Lt => value < bound,
+            Op::Gt => value > bound,
+            Op::Le => value <= bound,
+            Op::Ge => value >= bound,
+            Op::Eq => value == bound,
+            Op::Ne => value != bound,
+        }
+    }
+}
+
+impl fmt::Display for Op {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let op = match self {
+            Op::Lt => "<",
+            Op::Gt => ">",
+            Op::Le => "<=",
+            Op::Ge => ">=",
+            Op::Eq => "==",
+            Op::Ne => "!=",
+        };
+
+        write!(f, "{}", op)
+    }
+}
+
+/// Where a compiled rule sends a part, with workflows referred to by their index.
+#[derive(PartialEq, Debug, Clone, Copy)]
+enum Target {
+    Accepted,
+    Rejected,
+    Workflow(usize),
+    /// A workflow that isn't defined, by its index in the names of undefined workflows.
+    Undefined(usize),
+}
+
+struct CompiledRule {
+    prop: usize,
+    op: Op,
+    value: u32,
+    target: Target,
+}
+
+struct CompiledWorkflow {
+    rules: Vec<CompiledRule>,
+    default_target: Target,
+}
+
+/// Workflows with all names resolved to indices, so that evaluating a part needs
+/// neither hashing nor string comparisons.
+struct CompiledWorkflows {
+    workflows: Vec<CompiledWorkflow>,
+    /// Names of the workflows referred to without being defined, only an error once a part
+    /// is actually sent to one.
+    undefined: Vec<String>,
+    start: Target,
+}
+
+/// Why the workflows couldn't sort a part.
+#[derive(Debug, PartialEq)]
+enum FlowError {
+    /// A part was sent to a workflow that isn't defined, possibly `in` itself.
+    Undefined(String),
 }
 
-fn parse_workflow(flow_raw: String) -> Workflow {
+impl fmt::Display for FlowError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FlowError::Undefined(name) => write!(f, "workflow {} isn't defined", name),
+        }
+    }
+}
+
+impl Part {
+    fn total_rating(&self) -> u64 {
+        self.ratings.iter().map(|&r| u64::from(r)).sum()
+    }
+}
+
+/// Index of the attribute called `name`, which is added to the known attributes when it's new.
+fn attribute_index(attributes: &mut Vec<String>, name: &str) -> usize {
+    match attributes.iter().position(|a| a == name) {
+        Some(i) => i,
+        None => {
+            attributes.push(name.to_string());
+            attributes.len() - 1
+        }
+    }
+}
+
+fn parse_workflow(flow_raw: String, attributes: &mut Vec<String>) -> Workflow {
     let name_index = flow_raw.find('{').unwrap();
     let name = &flow_raw[0..name_index];
 
@@ -59,13 +141,16 @@ fn parse_workflow(flow_raw: String) -> Workflow {
         default_state: State::Accepted,
     };
 
-    let r = Regex::new(r"([xmas])(<|>)([0-9]+):([a-zA-Z]+)").unwrap();
+    let r = Regex::new(r"([a-zA-Z]+)(<=|>=|==|!=|<|>)([0-9]+):([a-zA-Z]+)").unwrap();
 
-    let mut flows_it = flow_raw.split(',').peekable();
+    // split the rules between the braces, so that a workflow without rules works as well
+    let mut flows_it = flow_raw[name_index + 1..flow_raw.len() - 1]
+        .split(',')
+        .peekable();
     while let Some(flow) = flows_it.next() {
         if flows_it.peek().is_none() {
             // final one, which is always only a State, so this becomes the default
-            workflows.default_state = match &flow[0..flow.len() - 1] {
+            workflows.default_state = match flow {
                 "A" => State::Accepted,
                 "R" => State::Rejected,
                 v => State::Passed(String::from(v)),
@@ -75,10 +160,14 @@ fn parse_workflow(flow_raw: String) -> Workflow {
 
         let caps = r.captures(flow).unwrap();
 
-        let prop = caps.get(1).unwrap().as_str().chars().next().unwrap();
-        let op = match caps.get(2).unwrap().as_str().chars().next().unwrap() {
-            '<' => Op::Lt,
-            '>' => Op::Gt,
+        let prop = attribute_index(attributes, caps.get(1).unwrap().as_str());
+        let op = match caps.get(2).unwrap().as_str() {
+            "<" => Op::Lt,
+            ">" => Op::Gt,
+            "<=" => Op::Le,
+            ">=" => Op::Ge,
+            "==" => Op::Eq,
+            "!=" => Op::Ne,
             _ => panic!("Unexpected operation"),
         };
         let value: u32 = caps.get(3).unwrap().as_str().parse().unwrap();
@@ -99,117 +188,384 @@ fn parse_workflow(flow_raw: String) -> Workflow {
     workflows
 }
 
-fn parse_part(part_raw: String) -> Part {
-    let r = Regex::new(r"\{x=([0-9]+),m=([0-9]+),a=([0-9]+),s=([0-9]+)\}").unwrap();
+/// Why a part couldn't be read.
+#[derive(Debug, PartialEq)]
+enum PartError {
+    /// The line isn't like `{x=787,m=2655}`.
+    Malformed(String),
+    /// The part isn't rated on an attribute that the workflows or other parts use, which
+    /// would otherwise count as a rating of 0.
+    Missing { part: String, attribute: String },
+}
+
+impl fmt::Display for PartError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PartError::Malformed(part) => write!(f, "{} isn't a part", part),
+            PartError::Missing { part, attribute } => {
+                write!(f, "{} has no rating for {}", part, attribute)
+            }
+        }
+    }
+}
 
-    let caps = r.captures(&part_raw).unwrap();
+fn parse_part(part_raw: String, attributes: &mut Vec<String>) -> Result<Part, PartError> {
+    let line = Regex::new(r"^\{[a-zA-Z]+=[0-9]+(,[a-zA-Z]+=[0-9]+)*\}$").unwrap();
+    let r = Regex::new(r"([a-zA-Z]+)=([0-9]+)").unwrap();
 
-    Part {
-        x: caps.get(1).unwrap().as_str().parse().unwrap(),
-        m: caps.get(2).unwrap().as_str().parse().unwrap(),
-        a: caps.get(3).unwrap().as_str().parse().unwrap(),
-        s: caps.get(4).unwrap().as_str().parse().unwrap(),
+    if !line.is_match(&part_raw) {
+        return Err(PartError::Malformed(part_raw));
+    }
+
+    let mut ratings: Vec<Option<u32>> = vec![None; attributes.len()];
+
+    for caps in r.captures_iter(&part_raw) {
+        let i = attribute_index(attributes, caps.get(1).unwrap().as_str());
+        if i >= ratings.len() {
+            ratings.resize(i + 1, None);
+        }
+
+        let rating = caps.get(2).unwrap().as_str().parse();
+        ratings[i] = Some(rating.map_err(|_| PartError::Malformed(part_raw.clone()))?);
+    }
+
+    match ratings.iter().position(|r| r.is_none()) {
+        Some(i) => Err(PartError::Missing {
+            part: part_raw,
+            attribute: attributes[i].clone(),
+        }),
+        None => Ok(Part {
+            ratings: ratings.into_iter().flatten().collect(),
+        }),
     }
 }
 
-fn parse_input(path: &str) -> (HashMap<String, Workflow>, Vec<Part>) {
+type Parsed = (HashMap<String, Workflow>, Vec<Part>, Vec<String>);
+
+fn parse_input(lines: Vec<String>) -> Result<Parsed, PartError> {
     let mut workflows: HashMap<String, Workflow> = HashMap::new();
     let mut parts: Vec<Part> = Vec::new();
+    let mut raw_parts: Vec<String> = Vec::new();
+    let mut attributes: Vec<String> = Vec::new();
+
+    let mut workflow_mode = true;
+    for line in lines {
+        if line.is_empty() {
+            // with the empty line we switch from parsing workflows
+            // to parsing parts
+            workflow_mode = false;
+            continue;
+        }
 
-    if let Ok(lines) = read_lines(path) {
-        let mut workflow_mode = true;
-        for raw_line in lines {
-            let line = raw_line.unwrap();
+        if workflow_mode {
+            let wf = parse_workflow(line, &mut attributes);
+            workflows.insert(wf.name.clone(), wf);
+        } else {
+            raw_parts.push(line.clone());
+            parts.push(parse_part(line, &mut attributes)?);
+        }
+    }
 
-            if line.is_empty() {
-                // with the empty line we switch from parsing workflows
-                // to parsing parts
-                workflow_mode = false;
-                continue;
-            }
+    // an attribute first seen in a later part is one the earlier parts aren't rated on
+    if let Some(i) = parts
+        .iter()
+        .position(|p| p.ratings.len() < attributes.len())
+    {
+        return Err(PartError::Missing {
+            part: raw_parts.swap_remove(i),
+            attribute: attributes[parts[i].ratings.len()].clone(),
+        });
+    }
+
+    Ok((workflows, parts, attributes))
+}
+
+/// Follows the workflows from `in` until the part is either accepted or rejected, and
+/// returns that final state, or the workflow it was sent to that isn't defined. `on_step` is
+/// called for every workflow visited, with the rule that fired, or `None` when the default
+/// state was used, and the state it led to.
+fn follow_workflows<'a>(
+    workflows: &'a HashMap<String, Workflow>,
+    part: &Part,
+    mut on_step: impl FnMut(&'a Workflow, Option<&'a Rule>, &State),
+) -> Result<State, FlowError> {
+    let mut result = State::Passed(String::from("in"));
+
+    // simple closure to copy a State
+    let result_clone = |result: &State| -> State {
+        match result {
+            State::Accepted => State::Accepted,
+            State::Rejected => State::Rejected,
+            State::Passed(str_val) => State::Passed(str_val.clone()),
+        }
+    };
+
+    // closure to do the actual operation on the given value
+    let cond_check = |part_val: u32, rule: &Rule| -> Result<State, bool> {
+        if rule.op.holds(part_val, rule.value) {
+            return Ok(result_clone(&rule.result));
+        }
+
+        Err(false)
+    };
+
+    // as long as the part has not reached either Accepted or Rejected state,
+    // we continue following the workflows
+    while let State::Passed(ref n) = &result {
+        let workflow = workflows
+            .get(n)
+            .ok_or_else(|| FlowError::Undefined(n.clone()))?;
 
-            if workflow_mode {
-                let wf = parse_workflow(line);
-                workflows.insert(wf.name.clone(), wf);
-            } else {
-                parts.push(parse_part(line));
+        let mut hit = None;
+
+        for rule in &workflow.rules {
+            if let Ok(new_result) = cond_check(part.ratings[rule.prop], rule) {
+                result = new_result;
+                hit = Some(rule);
+                break;
             }
         }
+
+        if hit.is_none() {
+            // no rules matched, so we fall back to the default rule
+            result = result_clone(&workflow.default_state);
+        }
+
+        on_step(workflow, hit, &result);
     }
 
-    (workflows, parts)
+    Ok(result)
 }
 
-fn get_accepted_parts_rating(workflows: HashMap<String, Workflow>, parts: Vec<Part>) -> u32 {
+fn get_accepted_parts_rating(
+    workflows: &HashMap<String, Workflow>,
+    parts: &[Part],
+) -> Result<u64, FlowError> {
     let mut sum = 0;
 
     for part in parts {
-        let mut result = State::Passed(String::from("in"));
-
-        // simple closure to copy a State
-        let result_clone = |result: &State| -> State {
-            match result {
-                State::Accepted => State::Accepted,
-                State::Rejected => State::Rejected,
-                State::Passed(str_val) => State::Passed(str_val.clone()),
-            }
-        };
+        let result = follow_workflows(workflows, part, |_, _, _| {})?;
 
-        // closure to do the actual operation on the given value
-        let cond_check = |part_val: u32, rule: &Rule| -> Result<State, bool> {
-            if rule.op == Op::Gt {
-                if part_val > rule.value {
-                    return Ok(result_clone(&rule.result));
-                }
-            } else if part_val < rule.value {
-                return Ok(result_clone(&rule.result));
-            }
+        // only for accepted parts do we sum all its internal values
+        if result == State::Accepted {
+            sum += part.total_rating();
+        }
+    }
+
+    Ok(sum)
+}
+
+fn state_str(state: &State) -> &str {
+    match state {
+        State::Accepted => "A",
+        State::Rejected => "R",
+        State::Passed(name) => name,
+    }
+}
 
-            Err(false)
+/// Describes how a part moves through the workflows, e.g.
+/// `{a=1222,m=2655,s=2876,x=787}: in (default qqz) -> qqz (s>2770:qs) -> ... -> A`,
+/// with the ratings in the order the attributes were found in and per workflow the rule that
+/// fired, or the default it fell back to.
+fn trace_part(
+    workflows: &HashMap<String, Workflow>,
+    part: &Part,
+    attributes: &[String],
+) -> Result<String, FlowError> {
+    let ratings: Vec<String> = attributes
+        .iter()
+        .zip(&part.ratings)
+        .map(|(a, r)| format!("{}={}", a, r))
+        .collect();
+
+    let mut steps: Vec<String> = Vec::new();
+    let result = follow_workflows(workflows, part, |workflow, rule, state| {
+        let fired = match rule {
+            Some(rule) => format!(
+                "{}{}{}:{}",
+                attributes[rule.prop],
+                rule.op,
+                rule.value,
+                state_str(state)
+            ),
+            None => format!("default {}", state_str(state)),
         };
+        steps.push(format!("{} ({})", workflow.name, fired));
+    })?;
+    steps.push(String::from(state_str(&result)));
 
-        // as long as the part has not reached either Accepted or Rejected state,
-        // we continue following the workflows
-        while let State::Passed(ref n) = &result {
-            let workflow = workflows.get(n).expect("Expected a workflow, got nothing");
-
-            let mut hit = false;
-
-            for rule in &workflow.rules {
-                if let Ok(new_result) = match rule.prop {
-                    'x' => cond_check(part.x, rule),
-                    'm' => cond_check(part.m, rule),
-                    'a' => cond_check(part.a, rule),
-                    's' => cond_check(part.s, rule),
-                    _ => panic!("Unexpected operation"),
-                } {
-                    result = new_result;
-                    hit = true;
-                    break;
+    Ok(format!("{{{}}}: {}", ratings.join(","), steps.join(" -> ")))
+}
+
+fn compile_workflows(workflows: &HashMap<String, Workflow>) -> CompiledWorkflows {
+    // sort the names so that the indices don't depend on the hashing order
+    let mut names: Vec<&String> = workflows.keys().collect();
+    names.sort();
+
+    let ids: HashMap<&String, usize> = names.iter().enumerate().map(|(i, &n)| (n, i)).collect();
+    let mut undefined: Vec<String> = Vec::new();
+
+    let mut target = |name: &String| -> Target {
+        match ids.get(name) {
+            Some(&id) => Target::Workflow(id),
+            None => match undefined.iter().position(|u| u == name) {
+                Some(i) => Target::Undefined(i),
+                None => {
+                    undefined.push(name.clone());
+                    Target::Undefined(undefined.len() - 1)
                 }
-            }
+            },
+        }
+    };
+    let mut state_target = |state: &State| -> Target {
+        match state {
+            State::Accepted => Target::Accepted,
+            State::Rejected => Target::Rejected,
+            State::Passed(name) => target(name),
+        }
+    };
 
-            if !hit {
-                // no rules matched, so we fall back to the default rule
-                result = result_clone(&workflow.default_state);
+    let compiled = names
+        .iter()
+        .map(|&name| {
+            let workflow = &workflows[name];
+
+            CompiledWorkflow {
+                rules: workflow
+                    .rules
+                    .iter()
+                    .map(|rule| CompiledRule {
+                        prop: rule.prop,
+                        op: rule.op,
+                        value: rule.value,
+                        target: state_target(&rule.result),
+                    })
+                    .collect(),
+                default_target: state_target(&workflow.default_state),
             }
+        })
+        .collect();
+    let start = state_target(&State::Passed(String::from("in")));
+
+    CompiledWorkflows {
+        workflows: compiled,
+        undefined,
+        start,
+    }
+}
+
+fn get_accepted_parts_rating_compiled(
+    compiled: &CompiledWorkflows,
+    parts: &[Part],
+) -> Result<u64, FlowError> {
+    let mut sum = 0;
+
+    for part in parts {
+        let mut target = compiled.start;
+
+        while let Target::Workflow(id) = target {
+            let workflow = &compiled.workflows[id];
+
+            target = workflow
+                .rules
+                .iter()
+                .find(|rule| rule.op.holds(part.ratings[rule.prop], rule.value))
+                .map_or(workflow.default_target, |rule| rule.target);
         }
 
-        // only for accepted parts do we sum all its internal values
-        if result == State::Accepted {
-            sum += part.x + part.m + part.a + part.s;
+        match target {
+            Target::Accepted => sum += part.total_rating(),
+            Target::Undefined(i) => {
+                return Err(FlowError::Undefined(compiled.undefined[i].clone()))
+            }
+            _ => {}
         }
     }
 
-    sum
+    Ok(sum)
+}
+
+/// Generates `count` parts rated on `nrof_attributes` attributes in 1..=4000 from a fixed seed,
+/// so that runs are comparable.
+fn generate_parts(count: usize, nrof_attributes: usize) -> Vec<Part> {
+    let mut rng = Xorshift::new();
+    let mut next = || rng.below(4000) as u32 + 1;
+
+    (0..count)
+        .map(|_| Part {
+            ratings: (0..nrof_attributes).map(|_| next()).collect(),
+        })
+        .collect()
+}
+
+fn run_benchmark(
+    workflows: &HashMap<String, Workflow>,
+    attributes: &[String],
+    count: usize,
+) -> Result<(), FlowError> {
+    let parts = generate_parts(count, attributes.len());
+
+    let now = Instant::now();
+    let sum = get_accepted_parts_rating(workflows, &parts)?;
+    println!("HashMap evaluation:  {} in {:?}", sum, now.elapsed());
+
+    let now = Instant::now();
+    let compiled = compile_workflows(workflows);
+    let sum = get_accepted_parts_rating_compiled(&compiled, &parts)?;
+    println!("Compiled evaluation: {} in {:?}", sum, now.elapsed());
+
+    Ok(())
 }
 
 fn main() {
-    let (workflows, parts) = parse_input("./puzzle.input");
+    let args: Vec<String> = env::args().collect();
+    let input = match Input::from_args(&args) {
+        Ok(input) => input,
+        Err(e) => {
+            eprintln!("Can't pick the input, {}", e);
+            return;
+        }
+    };
+    let lines = match input.read_lines() {
+        Ok(lines) => lines,
+        Err(e) => {
+            eprintln!("Can't read {}, {}", input, e);
+            return;
+        }
+    };
+    let (workflows, parts, attributes) = match parse_input(lines) {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            eprintln!("Can't use the parts, {}", e);
+            return;
+        }
+    };
+
+    if let Some(i) = args.iter().position(|a| a == "--bench") {
+        match args.get(i + 1).and_then(|n| n.parse().ok()) {
+            Some(count) => {
+                if let Err(e) = run_benchmark(&workflows, &attributes, count) {
+                    eprintln!("Can't sort the generated parts, {}", e);
+                }
+            }
+            None => eprintln!("Can't run the benchmark, --bench needs a number of parts"),
+        }
+        return;
+    }
 
-    let sum = get_accepted_parts_rating(workflows, parts);
+    if args.iter().any(|a| a == "--trace") {
+        for part in &parts {
+            match trace_part(&workflows, part, &attributes) {
+                Ok(trace) => println!("{}", trace),
+                Err(e) => eprintln!("Can't trace the part, {}", e),
+            }
+        }
+    }
 
-    println!("Sum of accepted parts is {}", sum);
+    match get_accepted_parts_rating_compiled(&compile_workflows(&workflows), &parts) {
+        Ok(sum) => println!("Sum of accepted parts is {}", sum),
+        Err(e) => eprintln!("Can't sort the parts, {}", e),
+    }
 }
 
 #[cfg(test)]
@@ -220,20 +576,23 @@ mod tests {
     fn test_parse_rule_should_generate_rule() {
         let flow_raw = String::from("px{a<2006:qkq,m>2090:A,rfg}");
 
-        let flow = parse_workflow(flow_raw);
+        let mut attributes: Vec<String> = Vec::new();
+
+        let flow = parse_workflow(flow_raw, &mut attributes);
 
         assert_eq!(flow.name, "px");
+        assert_eq!(attributes, vec!["a", "m"]);
         assert_eq!(flow.rules.len(), 2);
 
-        let r1 = flow.rules.get(0).unwrap();
+        let r1 = flow.rules.first().unwrap();
         let r2 = flow.rules.get(1).unwrap();
 
-        assert_eq!(r1.prop, 'a');
+        assert_eq!(r1.prop, 0);
         assert_eq!(r1.op, Op::Lt);
         assert_eq!(r1.value, 2006);
         assert_eq!(r1.result, State::Passed(String::from("qkq")));
 
-        assert_eq!(r2.prop, 'm');
+        assert_eq!(r2.prop, 1);
         assert_eq!(r2.op, Op::Gt);
         assert_eq!(r2.value, 2090);
         assert_eq!(r2.result, State::Accepted);
@@ -245,11 +604,195 @@ mod tests {
     fn test_parse_part_should_generate_part() {
         let part_raw = String::from("{x=787,m=2655,a=1222,s=2876}");
 
-        let part = parse_part(part_raw);
+        let mut attributes: Vec<String> = Vec::new();
+
+        let part = parse_part(part_raw, &mut attributes).unwrap();
+
+        assert_eq!(attributes, vec!["x", "m", "a", "s"]);
+        assert_eq!(part.ratings, vec![787, 2655, 1222, 2876]);
+    }
+
+    #[test]
+    fn test_parse_part_should_reject_incomplete_parts() {
+        let mut attributes: Vec<String> = vec![String::from("x"), String::from("m")];
+
+        assert_eq!(
+            parse_part(String::from("{x=787}"), &mut attributes).err(),
+            Some(PartError::Missing {
+                part: String::from("{x=787}"),
+                attribute: String::from("m")
+            })
+        );
+        assert_eq!(
+            parse_part(String::from("{x=787,m}"), &mut attributes).err(),
+            Some(PartError::Malformed(String::from("{x=787,m}")))
+        );
+        assert_eq!(
+            parse_part(String::from("{x=787,m=99999999999}"), &mut attributes).err(),
+            Some(PartError::Malformed(String::from("{x=787,m=99999999999}")))
+        );
+    }
+
+    #[test]
+    fn test_parse_input_should_reject_parts_before_a_new_attribute() {
+        let lines = ["in{x<10:A,R}", "", "{x=1}", "{x=2,y=3}"].map(String::from);
+
+        assert_eq!(
+            parse_input(lines.to_vec()).err(),
+            Some(PartError::Missing {
+                part: String::from("{x=1}"),
+                attribute: String::from("y")
+            })
+        );
+    }
+
+    #[test]
+    fn test_compiled_workflows_match_example() {
+        let (workflows, parts, _) = parse_input(Input::example(1).read_lines().unwrap()).unwrap();
+
+        let compiled = compile_workflows(&workflows);
+
+        assert_eq!(compiled.workflows.len(), workflows.len());
+        assert_eq!(get_accepted_parts_rating(&workflows, &parts), Ok(19114));
+        assert_eq!(
+            get_accepted_parts_rating_compiled(&compiled, &parts),
+            Ok(19114)
+        );
+    }
+
+    #[test]
+    fn test_compiled_workflows_match_generated_parts() {
+        let (workflows, _, attributes) =
+            parse_input(Input::puzzle().read_lines().unwrap()).unwrap();
+        let parts = generate_parts(10_000, attributes.len());
+
+        let compiled = compile_workflows(&workflows);
+
+        assert_eq!(
+            get_accepted_parts_rating_compiled(&compiled, &parts),
+            get_accepted_parts_rating(&workflows, &parts)
+        );
+    }
+
+    #[test]
+    fn test_undefined_workflows_only_fail_parts_sent_there() {
+        let mut attributes: Vec<String> = Vec::new();
+        let mut workflows: HashMap<String, Workflow> = HashMap::new();
+        let wf = parse_workflow(String::from("in{x<5:zz,R}"), &mut attributes);
+        workflows.insert(wf.name.clone(), wf);
+
+        let passing = vec![parse_part(String::from("{x=9}"), &mut attributes).unwrap()];
+        let failing = vec![parse_part(String::from("{x=1}"), &mut attributes).unwrap()];
+        let compiled = compile_workflows(&workflows);
+
+        assert_eq!(get_accepted_parts_rating(&workflows, &passing), Ok(0));
+        assert_eq!(
+            get_accepted_parts_rating_compiled(&compiled, &passing),
+            Ok(0)
+        );
+        assert_eq!(
+            get_accepted_parts_rating(&workflows, &failing),
+            Err(FlowError::Undefined(String::from("zz")))
+        );
+        assert_eq!(
+            get_accepted_parts_rating_compiled(&compiled, &failing),
+            Err(FlowError::Undefined(String::from("zz")))
+        );
+
+        let without_in = compile_workflows(&HashMap::new());
+        assert_eq!(get_accepted_parts_rating_compiled(&without_in, &[]), Ok(0));
+        assert_eq!(
+            get_accepted_parts_rating_compiled(&without_in, &passing),
+            Err(FlowError::Undefined(String::from("in")))
+        );
+    }
+
+    #[test]
+    fn test_parse_rule_should_accept_all_operators() {
+        let flow = parse_workflow(
+            String::from("in{x<=10:A,m>=20:R,a==30:qs,s!=40:A,x<5:R,R}"),
+            &mut Vec::new(),
+        );
+
+        let ops: Vec<Op> = flow.rules.iter().map(|r| r.op).collect();
+
+        assert_eq!(ops, vec![Op::Le, Op::Ge, Op::Eq, Op::Ne, Op::Lt]);
+        assert_eq!(flow.rules[2].result, State::Passed(String::from("qs")));
+    }
+
+    #[test]
+    fn test_inclusive_and_equality_operators() {
+        let mut workflows: HashMap<String, Workflow> = HashMap::new();
+        let mut attributes: Vec<String> = Vec::new();
+        for raw in ["in{x<=10:A,x>=20:lo,m==5:A,R}", "lo{s!=7:A,R}"] {
+            let wf = parse_workflow(String::from(raw), &mut attributes);
+            workflows.insert(wf.name.clone(), wf);
+        }
+
+        let parts: Vec<Part> = [
+            "{x=10,m=1,a=1,s=1}", // x <= 10
+            "{x=11,m=1,a=1,s=1}", // rejected
+            "{x=11,m=5,a=1,s=1}", // m == 5
+            "{x=20,m=1,a=1,s=7}", // x >= 20 but s == 7
+            "{x=20,m=1,a=1,s=8}", // x >= 20 and s != 7
+        ]
+        .iter()
+        .map(|raw| parse_part(String::from(*raw), &mut attributes).unwrap())
+        .collect();
+
+        let expected = 13 + 18 + 30;
+
+        assert_eq!(get_accepted_parts_rating(&workflows, &parts), Ok(expected));
+        assert_eq!(
+            get_accepted_parts_rating_compiled(&compile_workflows(&workflows), &parts),
+            Ok(expected)
+        );
+    }
+
+    #[test]
+    fn test_custom_attributes() {
+        let mut workflows: HashMap<String, Workflow> = HashMap::new();
+        let mut attributes: Vec<String> = Vec::new();
+        for raw in ["in{weight>10:heavy,A}", "heavy{colour==3:A,size<5:A,R}"] {
+            let wf = parse_workflow(String::from(raw), &mut attributes);
+            workflows.insert(wf.name.clone(), wf);
+        }
 
-        assert_eq!(part.x, 787);
-        assert_eq!(part.m, 2655);
-        assert_eq!(part.a, 1222);
-        assert_eq!(part.s, 2876);
+        let parts: Vec<Part> = [
+            "{weight=5,colour=1,size=9,age=2}",
+            "{weight=15,colour=3,size=9,age=2}",
+            "{weight=15,colour=1,size=4,age=2}",
+            "{weight=15,colour=1,size=9,age=2}",
+        ]
+        .iter()
+        .map(|raw| parse_part(String::from(*raw), &mut attributes).unwrap())
+        .collect();
+
+        assert_eq!(attributes, vec!["weight", "colour", "size", "age"]);
+        assert_eq!(
+            get_accepted_parts_rating(&workflows, &parts),
+            Ok(17 + 29 + 22)
+        );
+        assert_eq!(
+            get_accepted_parts_rating_compiled(&compile_workflows(&workflows), &parts),
+            Ok(17 + 29 + 22)
+        );
+    }
+
+    #[test]
+    fn test_trace_part_example() {
+        let (workflows, parts, attributes) =
+            parse_input(Input::example(1).read_lines().unwrap()).unwrap();
+
+        assert_eq!(
+            trace_part(&workflows, &parts[0], &attributes).unwrap(),
+            "{a=1222,m=2655,s=2876,x=787}: in (default qqz) -> qqz (s>2770:qs) -> qs (default lnx) \
+             -> lnx (m>1548:A) -> A"
+        );
+        assert_eq!(
+            trace_part(&workflows, &parts[1], &attributes).unwrap(),
+            "{a=2067,m=44,s=496,x=1679}: in (s<1351:px) -> px (default rfg) -> rfg (s<537:gd) \
+             -> gd (default R) -> R"
+        );
     }
 }
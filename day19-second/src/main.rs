@@ -1,26 +1,50 @@
-use std::{
-    collections::HashMap,
-    fs::File,
-    io::{self, BufRead},
-    path::Path,
-    vec,
-};
+use std::{collections::HashMap, env, fmt, vec};
 
+use aoc::Input;
 use regex::Regex;
 
+/// A box of parts, with one inclusive range of ratings per attribute parsed from the input,
+/// and the workflows the box went through to get to its state.
 #[derive(Debug)]
 struct Part {
     state: State,
-    x: (u32, u32),
-    m: (u32, u32),
-    a: (u32, u32),
-    s: (u32, u32),
+    ranges: Vec<(u32, u32)>,
+    path: Vec<String>,
+}
+
+impl Part {
+    /// Number of parts in the box, which fits as `parse_domains` rejects domains holding more.
+    fn combinations(&self) -> u128 {
+        self.ranges
+            .iter()
+            .map(|range| u128::from(range.1 - range.0) + 1)
+            .product()
+    }
 }
 
 #[derive(PartialEq, Debug)]
 enum Op {
     Lt,
     Gt,
+    Le,
+    Ge,
+    Eq,
+    Ne,
+}
+
+impl fmt::Display for Op {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let op = match self {
+            Op::Lt => "<",
+            Op::Gt => ">",
+            Op::Le => "<=",
+            Op::Ge => ">=",
+            Op::Eq => "==",
+            Op::Ne => "!=",
+        };
+
+        write!(f, "{}", op)
+    }
 }
 
 #[derive(PartialEq, Debug, Clone)]
@@ -30,6 +54,9 @@ enum State {
     Passed(String),
 }
 
+/// Disjoint inclusive ranges of attribute values, in ascending order.
+type Ranges = Vec<(u32, u32)>;
+
 struct Workflow {
     name: String,
     rules: Vec<Rule>,
@@ -37,21 +64,24 @@ struct Workflow {
 }
 
 struct Rule {
-    prop: char,
+    prop: usize,
     op: Op,
     value: u32,
     result: State,
 }
 
-fn read_lines<P>(filename: P) -> io::Result<io::Lines<io::BufReader<File>>>
-where
-    P: AsRef<Path>,
-{
-    let file = File::open(filename)?;
-    Ok(io::BufReader::new(file).lines())
+/// Index of the attribute called `name`, which is added to the known attributes when it's new.
+fn attribute_index(attributes: &mut Vec<String>, name: &str) -> usize {
+    match attributes.iter().position(|a| a == name) {
+        Some(i) => i,
+        None => {
+            attributes.push(name.to_string());
+            attributes.len() - 1
+        }
+    }
 }
 
-fn parse_workflow(flow_raw: String) -> Workflow {
+fn parse_workflow(flow_raw: String, attributes: &mut Vec<String>) -> Workflow {
     let name_index = flow_raw.find('{').unwrap();
     let name = &flow_raw[0..name_index];
 
@@ -61,13 +91,16 @@ fn parse_workflow(flow_raw: String) -> Workflow {
         default_state: State::Accepted,
     };
 
-    let r = Regex::new(r"([xmas])(<|>)([0-9]+):([a-zA-Z]+)").unwrap();
+    let r = Regex::new(r"([a-zA-Z]+)(<=|>=|==|!=|<|>)([0-9]+):([a-zA-Z]+)").unwrap();
 
-    let mut flows_it = flow_raw.split(',').peekable();
+    // split the rules between the braces, so that a workflow without rules works as well
+    let mut flows_it = flow_raw[name_index + 1..flow_raw.len() - 1]
+        .split(',')
+        .peekable();
     while let Some(flow) = flows_it.next() {
         if flows_it.peek().is_none() {
             // final one, which is always only a State, so this becomes the default
-            workflows.default_state = match &flow[0..flow.len() - 1] {
+            workflows.default_state = match flow {
                 "A" => State::Accepted,
                 "R" => State::Rejected,
                 v => State::Passed(String::from(v)),
@@ -77,10 +110,14 @@ fn parse_workflow(flow_raw: String) -> Workflow {
 
         let caps = r.captures(flow).unwrap();
 
-        let prop = caps.get(1).unwrap().as_str().chars().next().unwrap();
-        let op = match caps.get(2).unwrap().as_str().chars().next().unwrap() {
-            '<' => Op::Lt,
-            '>' => Op::Gt,
+        let prop = attribute_index(attributes, caps.get(1).unwrap().as_str());
+        let op = match caps.get(2).unwrap().as_str() {
+            "<" => Op::Lt,
+            ">" => Op::Gt,
+            "<=" => Op::Le,
+            ">=" => Op::Ge,
+            "==" => Op::Eq,
+            "!=" => Op::Ne,
             _ => panic!("Unexpected operation"),
         };
         let value: u32 = caps.get(3).unwrap().as_str().parse().unwrap();
@@ -101,196 +138,893 @@ fn parse_workflow(flow_raw: String) -> Workflow {
     workflows
 }
 
-fn parse_input(path: &str) -> HashMap<String, Workflow> {
-    let mut workflows: HashMap<String, Workflow> = HashMap::new();
+/// The domain every attribute has, unless given otherwise.
+const DEFAULT_DOMAIN: (u32, u32) = (1, 4000);
 
-    if let Ok(lines) = read_lines(path) {
-        for raw_line in lines {
-            let line = raw_line.unwrap();
+/// Why a `--domain` argument can't be used.
+#[derive(Debug, PartialEq)]
+enum DomainError {
+    /// The argument isn't like `x=1-4000`, or there's none after `--domain`.
+    Malformed(String),
+    /// The attribute isn't used by the workflows or parts.
+    UnknownAttribute(String),
+    /// The lower bound lies above the upper one, so the domain has no ratings at all.
+    Empty(String),
+    /// The domains hold more parts than a `u128` can count, e.g. `0-4294967295` for all four
+    /// attributes, so their combinations can't be counted either.
+    TooLarge,
+}
 
-            if line.is_empty() {
-                break;
+impl fmt::Display for DomainError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DomainError::Malformed(arg) => write!(f, "`{}` isn't like x=1-4000", arg),
+            DomainError::UnknownAttribute(name) => write!(f, "there's no attribute {}", name),
+            DomainError::Empty(arg) => write!(f, "`{}` has its bounds the wrong way round", arg),
+            DomainError::TooLarge => write!(f, "the domains hold more parts than fit in a u128"),
+        }
+    }
+}
+
+/// The inclusive domain of ratings per attribute, which defaults to `DEFAULT_DOMAIN` and can
+/// be set per attribute with arguments like `--domain x=0-65535`.
+fn parse_domains(args: &[String], attributes: &[String]) -> Result<Vec<(u32, u32)>, DomainError> {
+    let domain_reg = Regex::new(r"^([a-zA-Z]+)=([0-9]+)-([0-9]+)$").unwrap();
+    let mut domains = vec![DEFAULT_DOMAIN; attributes.len()];
+
+    for (i, arg) in args.iter().enumerate() {
+        if arg != "--domain" {
+            continue;
+        }
+
+        let spec = args.get(i + 1).map_or("", |s| s.as_str());
+        let malformed = || DomainError::Malformed(spec.to_string());
+
+        let caps = domain_reg.captures(spec).ok_or_else(malformed)?;
+        let attribute = attributes
+            .iter()
+            .position(|a| *a == caps[1])
+            .ok_or_else(|| DomainError::UnknownAttribute(caps[1].to_string()))?;
+        let lo: u32 = caps[2].parse().map_err(|_| malformed())?;
+        let hi: u32 = caps[3].parse().map_err(|_| malformed())?;
+
+        if lo > hi {
+            return Err(DomainError::Empty(spec.to_string()));
+        }
+
+        domains[attribute] = (lo, hi);
+    }
+
+    // the accepted boxes are disjoint, so if all parts can be counted, any of them can
+    let parts = domains.iter().try_fold(1_u128, |product, &(lo, hi)| {
+        product.checked_mul(u128::from(hi - lo) + 1)
+    });
+    if parts.is_none() {
+        return Err(DomainError::TooLarge);
+    }
+
+    Ok(domains)
+}
+
+/// Why the part given to `--explain` can't be explained.
+#[derive(Debug, PartialEq)]
+enum PartError {
+    /// The argument isn't like `{x=787,m=2655}`, or there's none after `--explain`.
+    Malformed(String),
+    /// The part is rated on an attribute that the workflows and parts don't use.
+    UnknownAttribute { part: String, attribute: String },
+    /// The part isn't rated on an attribute that the workflows or parts use, which would
+    /// otherwise count as a rating of 0.
+    Missing { part: String, attribute: String },
+    /// A rating lies outside the domain of its attribute, so no box can hold the part.
+    OutOfDomain { part: String, attribute: String },
+}
+
+impl fmt::Display for PartError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PartError::Malformed(part) => write!(f, "`{}` isn't a part", part),
+            PartError::UnknownAttribute { part, attribute } => {
+                write!(
+                    f,
+                    "{} is rated on {}, which isn't an attribute",
+                    part, attribute
+                )
+            }
+            PartError::Missing { part, attribute } => {
+                write!(f, "{} has no rating for {}", part, attribute)
             }
+            PartError::OutOfDomain { part, attribute } => {
+                write!(f, "{} is rated outside the domain of {}", part, attribute)
+            }
+        }
+    }
+}
+
+/// The ratings of a part given like one in the input, e.g. `{x=787,m=2655,a=1222,s=2876}`,
+/// indexed the same as `attributes`.
+fn parse_part(
+    part_raw: &str,
+    attributes: &[String],
+    domains: &[(u32, u32)],
+) -> Result<Vec<u32>, PartError> {
+    let line = Regex::new(r"^\{[a-zA-Z]+=[0-9]+(,[a-zA-Z]+=[0-9]+)*\}$").unwrap();
+    let r = Regex::new(r"([a-zA-Z]+)=([0-9]+)").unwrap();
+
+    if !line.is_match(part_raw) {
+        return Err(PartError::Malformed(part_raw.to_string()));
+    }
+
+    let mut ratings: Vec<Option<u32>> = vec![None; attributes.len()];
+
+    for caps in r.captures_iter(part_raw) {
+        let i = attributes
+            .iter()
+            .position(|a| *a == caps[1])
+            .ok_or_else(|| PartError::UnknownAttribute {
+                part: part_raw.to_string(),
+                attribute: caps[1].to_string(),
+            })?;
+        let rating: u32 = caps[2]
+            .parse()
+            .map_err(|_| PartError::Malformed(part_raw.to_string()))?;
+
+        if rating < domains[i].0 || rating > domains[i].1 {
+            return Err(PartError::OutOfDomain {
+                part: part_raw.to_string(),
+                attribute: attributes[i].clone(),
+            });
+        }
+
+        ratings[i] = Some(rating);
+    }
+
+    match ratings.iter().position(|r| r.is_none()) {
+        Some(i) => Err(PartError::Missing {
+            part: part_raw.to_string(),
+            attribute: attributes[i].clone(),
+        }),
+        None => Ok(ratings.into_iter().flatten().collect()),
+    }
+}
+
+fn parse_input(lines: Vec<String>) -> (HashMap<String, Workflow>, Vec<String>) {
+    let mut workflows: HashMap<String, Workflow> = HashMap::new();
+    let mut attributes: Vec<String> = Vec::new();
+
+    // the parts themselves don't matter here, but the attributes they're rated on do
+    let part_reg = Regex::new(r"([a-zA-Z]+)=[0-9]+").unwrap();
 
-            let wf = parse_workflow(line);
+    let mut workflow_mode = true;
+    for line in lines {
+        if line.is_empty() {
+            workflow_mode = false;
+            continue;
+        }
+
+        if workflow_mode {
+            let wf = parse_workflow(line, &mut attributes);
             workflows.insert(wf.name.clone(), wf);
+        } else {
+            for caps in part_reg.captures_iter(&line) {
+                attribute_index(&mut attributes, caps.get(1).unwrap().as_str());
+            }
         }
     }
 
-    workflows
+    (workflows, attributes)
 }
 
-fn get_possible_distinct_combinations(workflows: HashMap<String, Workflow>) -> u64 {
+/// Splits `range` into the sub-ranges whose values meet the condition `op value`, and the
+/// sub-ranges whose values don't.
+fn split_range(range: (u32, u32), op: &Op, value: u32) -> (Ranges, Ranges) {
+    let value = i64::from(value);
+    let below = (i64::MIN, value - 1);
+    let at = (value, value);
+    let above = (value + 1, i64::MAX);
+
+    let (matched, unmatched) = match op {
+        Op::Lt => (vec![below], vec![at, above]),
+        Op::Gt => (vec![above], vec![below, at]),
+        Op::Le => (vec![below, at], vec![above]),
+        Op::Ge => (vec![at, above], vec![below]),
+        Op::Eq => (vec![at], vec![below, above]),
+        Op::Ne => (vec![below, above], vec![at]),
+    };
+
+    // clip the bounds to the range and join the ones that touch
+    let clip = |bounds: Vec<(i64, i64)>| -> Vec<(u32, u32)> {
+        let mut ranges: Vec<(u32, u32)> = Vec::new();
+
+        for (lo, hi) in bounds {
+            let lo = lo.max(i64::from(range.0));
+            let hi = hi.min(i64::from(range.1));
+
+            if lo > hi {
+                continue;
+            }
+
+            match ranges.last_mut() {
+                Some(last) if i64::from(last.1) + 1 == lo => last.1 = hi as u32,
+                _ => ranges.push((lo as u32, hi as u32)),
+            }
+        }
+
+        ranges
+    };
+
+    (clip(matched), clip(unmatched))
+}
+
+fn get_possible_distinct_combinations(
+    workflows: HashMap<String, Workflow>,
+    domains: &[(u32, u32)],
+) -> u128 {
+    get_accepted_boxes(&workflows, domains)
+        .iter()
+        .map(|part| part.combinations())
+        .sum()
+}
+
+/// Splits all possible parts into disjoint boxes following the workflows, and returns the
+/// boxes that end up accepted.
+fn get_accepted_boxes(workflows: &HashMap<String, Workflow>, domains: &[(u32, u32)]) -> Vec<Part> {
     let start = Part {
         state: State::Passed(String::from("in")),
-        x: (1, 4000),
-        m: (1, 4000),
-        a: (1, 4000),
-        s: (1, 4000),
+        ranges: domains.to_vec(),
+        path: vec![],
     };
 
     let mut possible_parts = vec![start];
-    let mut nrof_combinations: u64 = 0;
+    let mut accepted_parts: Vec<Part> = Vec::new();
 
-    // simple closure to clone a State
-    let state_clone = |state: &State| -> State {
-        match state {
-            State::Accepted => State::Accepted,
-            State::Rejected => State::Rejected,
-            State::Passed(str_val) => State::Passed(str_val.clone()),
+    while let Some(part) = possible_parts.pop() {
+        let name = match part.state {
+            State::Passed(name) => name,
+            State::Accepted => {
+                // this is an accepted part, so we are done with it
+                accepted_parts.push(part);
+                continue;
+            }
+            State::Rejected => continue,
+        };
+
+        let workflow = workflows
+            .get(&name)
+            .expect("Expected a workflow, got nothing");
+
+        let mut path = part.path;
+        path.push(name);
+
+        // every rule splits the boxes no earlier rule matched into the sub-boxes that match it,
+        // which move on to the rule's state, and the ones that don't, which go to the next rule
+        let mut unmatched_boxes: Vec<Ranges> = vec![part.ranges];
+
+        for rule in &workflow.rules {
+            let mut still_unmatched: Vec<Ranges> = Vec::new();
+
+            for ranges in unmatched_boxes {
+                let (matched, unmatched) = split_range(ranges[rule.prop], &rule.op, rule.value);
+
+                for range in matched {
+                    let mut sub_ranges = ranges.clone();
+                    sub_ranges[rule.prop] = range;
+
+                    possible_parts.push(Part {
+                        state: rule.result.clone(),
+                        ranges: sub_ranges,
+                        path: path.clone(),
+                    });
+                }
+
+                for range in unmatched {
+                    let mut sub_ranges = ranges.clone();
+                    sub_ranges[rule.prop] = range;
+                    still_unmatched.push(sub_ranges);
+                }
+            }
+
+            unmatched_boxes = still_unmatched;
         }
-    };
 
-    // closure to check the operator and apply changes to the current part,
-    // as well as to potentially return a new (split off) part
-    let cond_check = |part_state: &mut State,
-                      part_val: &mut (u32, u32),
-                      rule: &Rule|
-     -> Option<((u32, u32), State)> {
-        if rule.op == Op::Gt {
-            // op is >
-            if part_val.0 > rule.value {
-                // this part fully covers the boundary, so we can follow the move
-                *part_state = state_clone(&rule.result);
-                return None;
-            } else if part_val.0 <= rule.value && part_val.1 > rule.value {
-                // split the current part into two. We update the current one so that
-                // it matches the part above the boundary, and create a new one that
-                // covers the part below the boundary
-                let new_part_val = (part_val.0, rule.value);
-                let new_part_state = state_clone(part_state);
-
-                // modify the remainder of the current part
-                *part_val = (rule.value + 1, part_val.1);
-                *part_state = state_clone(&rule.result);
-
-                return Some((new_part_val, new_part_state));
+        // whatever none of the rules matched falls back to the default rule
+        for ranges in unmatched_boxes {
+            possible_parts.push(Part {
+                state: workflow.default_state.clone(),
+                ranges,
+                path: path.clone(),
+            });
+        }
+    }
+
+    accepted_parts
+}
+
+fn boxes_to_csv(boxes: &[Part], attributes: &[String]) -> String {
+    let mut header: Vec<String> = vec![String::from("path")];
+    for attribute in attributes {
+        header.push(format!("{}_from", attribute));
+        header.push(format!("{}_to", attribute));
+    }
+    header.push(String::from("combinations"));
+
+    let mut csv = header.join(",") + "\n";
+
+    for part in boxes {
+        let mut row: Vec<String> = vec![part.path.join(">")];
+        for range in &part.ranges {
+            row.push(range.0.to_string());
+            row.push(range.1.to_string());
+        }
+        row.push(part.combinations().to_string());
+
+        csv += &(row.join(",") + "\n");
+    }
+
+    csv
+}
+
+fn boxes_to_json(boxes: &[Part], attributes: &[String]) -> String {
+    let entries: Vec<String> = boxes
+        .iter()
+        .map(|part| {
+            let path: Vec<String> = part.path.iter().map(|n| format!("\"{}\"", n)).collect();
+            let ranges: Vec<String> = attributes
+                .iter()
+                .zip(&part.ranges)
+                .map(|(attribute, range)| format!("\"{}\":[{},{}]", attribute, range.0, range.1))
+                .collect();
+
+            format!(
+                "  {{\"path\":[{}],\"ranges\":{{{}}},\"combinations\":{}}}",
+                path.join(","),
+                ranges.join(","),
+                part.combinations()
+            )
+        })
+        .collect();
+
+    format!("[\n{}\n]\n", entries.join(",\n"))
+}
+
+/// Something the static analysis found to be wrong with, or redundant in, the workflows.
+#[derive(PartialEq, Debug)]
+enum Finding {
+    MissingStart,
+    UndefinedTarget { workflow: String, target: String },
+    Unreachable(String),
+    Cycle(Vec<String>),
+    DeadRule { workflow: String, rule: usize },
+    ConstantOutcome { workflow: String, state: State },
+}
+
+impl Finding {
+    /// Whether this finding keeps the workflows from being evaluated at all.
+    fn is_fatal(&self) -> bool {
+        matches!(
+            self,
+            Finding::MissingStart | Finding::UndefinedTarget { .. } | Finding::Cycle(_)
+        )
+    }
+}
+
+impl fmt::Display for Finding {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Finding::MissingStart => write!(f, "there is no workflow `in` to start from"),
+            Finding::UndefinedTarget { workflow, target } => write!(
+                f,
+                "workflow `{}` sends parts to undefined workflow `{}`",
+                workflow, target
+            ),
+            Finding::Unreachable(workflow) => {
+                write!(f, "workflow `{}` is unreachable from `in`", workflow)
             }
+            Finding::Cycle(names) => write!(f, "workflows form a cycle: {}", names.join(" -> ")),
+            Finding::DeadRule { workflow, rule } => write!(
+                f,
+                "rule {} of workflow `{}` can never fire",
+                rule + 1,
+                workflow
+            ),
+            Finding::ConstantOutcome { workflow, state } => write!(
+                f,
+                "workflow `{}` always ends up {}",
+                workflow,
+                if *state == State::Accepted {
+                    "accepted"
+                } else {
+                    "rejected"
+                }
+            ),
+        }
+    }
+}
+
+/// Names of the workflows a workflow can send parts to, in rule order.
+fn workflow_targets(workflow: &Workflow) -> Vec<&String> {
+    workflow
+        .rules
+        .iter()
+        .map(|rule| &rule.result)
+        .chain([&workflow.default_state])
+        .filter_map(|state| match state {
+            State::Passed(name) => Some(name),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Looks at the rules of a single workflow in isolation and returns the indices of the rules
+/// that can never fire because earlier rules already took all their values, together with the
+/// states that parts can actually leave the workflow with.
+fn live_rules<'a>(workflow: &'a Workflow, domains: &[(u32, u32)]) -> (Vec<usize>, Vec<&'a State>) {
+    let mut dead: Vec<usize> = Vec::new();
+    let mut live: Vec<&State> = Vec::new();
+
+    // boxes of values that no earlier rule has taken yet
+    let mut remaining: Vec<Vec<(u32, u32)>> = vec![domains.to_vec()];
+
+    for (i, rule) in workflow.rules.iter().enumerate() {
+        let mut fired = false;
+        let mut next_remaining: Vec<Vec<(u32, u32)>> = Vec::new();
+
+        for ranges in remaining {
+            let (matched, unmatched) = split_range(ranges[rule.prop], &rule.op, rule.value);
+            fired |= !matched.is_empty();
+
+            for range in unmatched {
+                let mut next = ranges.clone();
+                next[rule.prop] = range;
+                next_remaining.push(next);
+            }
+        }
+
+        if fired {
+            live.push(&rule.result);
         } else {
-            // op is <
-            if part_val.1 < rule.value {
-                // this part fully covers the boundary, so we can follow the move
-                *part_state = state_clone(&rule.result);
-            } else if part_val.0 < rule.value && part_val.1 >= rule.value {
-                // split current part into two
-                let new_part_val = (rule.value, part_val.1);
-                let new_part_state = state_clone(part_state);
-
-                // modify the remainder of the current part
-                *part_val = (part_val.0, rule.value - 1);
-                *part_state = state_clone(&rule.result);
-
-                return Some((new_part_val, new_part_state));
+            dead.push(i);
+        }
+
+        remaining = next_remaining;
+    }
+
+    if !remaining.is_empty() {
+        live.push(&workflow.default_state);
+    }
+
+    (dead, live)
+}
+
+/// Finds the cycles in the workflow graph, each listed from its first workflow back to itself.
+fn find_cycles(workflows: &HashMap<String, Workflow>, names: &[&String]) -> Vec<Vec<String>> {
+    fn visit(
+        name: &String,
+        workflows: &HashMap<String, Workflow>,
+        done: &mut Vec<String>,
+        path: &mut Vec<String>,
+        cycles: &mut Vec<Vec<String>>,
+    ) {
+        if let Some(start) = path.iter().position(|n| n == name) {
+            let mut cycle = path[start..].to_vec();
+            cycle.push(name.clone());
+            cycles.push(cycle);
+            return;
+        }
+
+        if done.contains(name) {
+            return;
+        }
+
+        if let Some(workflow) = workflows.get(name) {
+            path.push(name.clone());
+            for target in workflow_targets(workflow) {
+                visit(target, workflows, done, path, cycles);
+            }
+            path.pop();
+        }
+
+        done.push(name.clone());
+    }
+
+    let mut done: Vec<String> = Vec::new();
+    let mut cycles: Vec<Vec<String>> = Vec::new();
+
+    for name in names {
+        visit(name, workflows, &mut done, &mut Vec::new(), &mut cycles);
+    }
+
+    cycles
+}
+
+/// Checks the workflows for undefined targets, unreachable workflows, cycles, rules that can
+/// never fire and workflows that always lead to the same outcome.
+fn analyse_workflows(
+    workflows: &HashMap<String, Workflow>,
+    domains: &[(u32, u32)],
+) -> Vec<Finding> {
+    let mut findings: Vec<Finding> = Vec::new();
+
+    // sort the names so that the findings don't depend on the hashing order
+    let mut names: Vec<&String> = workflows.keys().collect();
+    names.sort();
+
+    if !workflows.contains_key("in") {
+        findings.push(Finding::MissingStart);
+    }
+
+    for name in &names {
+        for target in workflow_targets(&workflows[*name]) {
+            if !workflows.contains_key(target) {
+                findings.push(Finding::UndefinedTarget {
+                    workflow: name.to_string(),
+                    target: target.clone(),
+                });
             }
         }
+    }
+
+    let mut reachable: Vec<&String> = Vec::new();
+    let mut queue: Vec<&String> = workflows.keys().filter(|n| *n == "in").collect();
+    while let Some(name) = queue.pop() {
+        if reachable.contains(&name) {
+            continue;
+        }
+        reachable.push(name);
 
-        // no split off part was created
-        None
+        if let Some(workflow) = workflows.get(name) {
+            queue.extend(workflow_targets(workflow));
+        }
+    }
+
+    for name in &names {
+        if !reachable.contains(name) {
+            findings.push(Finding::Unreachable(name.to_string()));
+        }
+    }
+
+    findings.extend(
+        find_cycles(workflows, &names)
+            .into_iter()
+            .map(Finding::Cycle),
+    );
+
+    let mut live: HashMap<&String, Vec<&State>> = HashMap::new();
+    for name in &names {
+        let (dead, live_states) = live_rules(&workflows[*name], domains);
+
+        for rule in dead {
+            findings.push(Finding::DeadRule {
+                workflow: name.to_string(),
+                rule,
+            });
+        }
+
+        live.insert(name, live_states);
+    }
+
+    // which of accepted and rejected each workflow can end up in, or None when that
+    // depends on an undefined workflow or a cycle
+    fn outcomes<'a>(
+        name: &'a String,
+        live: &HashMap<&'a String, Vec<&'a State>>,
+        memo: &mut HashMap<&'a String, Option<(bool, bool)>>,
+    ) -> Option<(bool, bool)> {
+        if let Some(outcome) = memo.get(name) {
+            return *outcome;
+        }
+
+        // mark the workflow as unknown while we're in it, so that cycles end up unknown
+        memo.insert(name, None);
+
+        let mut outcome = Some((false, false));
+        for &state in live.get(name)? {
+            let (accepted, rejected) = match state {
+                State::Accepted => (true, false),
+                State::Rejected => (false, true),
+                State::Passed(target) => outcomes(target, live, memo)?,
+            };
+            outcome = outcome.map(|o| (o.0 || accepted, o.1 || rejected));
+        }
+
+        memo.insert(name, outcome);
+        outcome
+    }
+
+    let mut memo: HashMap<&String, Option<(bool, bool)>> = HashMap::new();
+    for name in &names {
+        let state = match outcomes(name, &live, &mut memo) {
+            Some((true, false)) => State::Accepted,
+            Some((false, true)) => State::Rejected,
+            _ => continue,
+        };
+
+        findings.push(Finding::ConstantOutcome {
+            workflow: name.to_string(),
+            state,
+        });
+    }
+
+    findings
+}
+
+/// Formats a workflow in the same syntax it's parsed from.
+fn format_workflow(workflow: &Workflow, attributes: &[String]) -> String {
+    let state_str = |state: &State| -> String {
+        match state {
+            State::Accepted => String::from("A"),
+            State::Rejected => String::from("R"),
+            State::Passed(name) => name.clone(),
+        }
     };
 
-    while let Some(mut part) = possible_parts.pop() {
-        if let State::Passed(workflow_state) = &part.state {
-            let workflow = workflows
-                .get(workflow_state)
-                .expect("Expected a workflow, got nothing");
-
-            let old_state = workflow_state.clone();
-
-            for rule in &workflow.rules {
-                match rule.prop {
-                    'x' => {
-                        if let Some(new_part) = cond_check(&mut part.state, &mut part.x, rule) {
-                            // we have to create a new Part
-                            possible_parts.push(Part {
-                                state: new_part.1,
-                                x: new_part.0,
-                                m: part.m,
-                                a: part.a,
-                                s: part.s,
-                            })
-                        }
-                    }
-                    'm' => {
-                        if let Some(new_part) = cond_check(&mut part.state, &mut part.m, rule) {
-                            // we have to create a new Part
-                            possible_parts.push(Part {
-                                state: new_part.1,
-                                x: part.x,
-                                m: new_part.0,
-                                a: part.a,
-                                s: part.s,
-                            })
-                        }
-                    }
-                    'a' => {
-                        if let Some(new_part) = cond_check(&mut part.state, &mut part.a, rule) {
-                            // we have to create a new Part
-                            possible_parts.push(Part {
-                                state: new_part.1,
-                                x: part.x,
-                                m: part.m,
-                                a: new_part.0,
-                                s: part.s,
-                            })
-                        }
-                    }
-                    's' => {
-                        if let Some(new_part) = cond_check(&mut part.state, &mut part.s, rule) {
-                            // we have to create a new Part
-                            possible_parts.push(Part {
-                                state: new_part.1,
-                                x: part.x,
-                                m: part.m,
-                                a: part.a,
-                                s: new_part.0,
-                            })
-                        }
-                    }
-                    _ => panic!("Unexpected operator"),
-                }
+    let mut flows: Vec<String> = workflow
+        .rules
+        .iter()
+        .map(|rule| {
+            format!(
+                "{}{}{}:{}",
+                attributes[rule.prop],
+                rule.op,
+                rule.value,
+                state_str(&rule.result)
+            )
+        })
+        .collect();
+    flows.push(state_str(&workflow.default_state));
 
-                if let State::Passed(new_state) = &part.state {
-                    if old_state != *new_state {
-                        // the state has changed, so we can break from checking other rules
-                        break;
-                    }
-                }
+    format!("{}{{{}}}", workflow.name, flows.join(","))
+}
+
+/// Replaces every reference to workflow `name` by `state`.
+fn replace_target(workflows: &mut HashMap<String, Workflow>, name: &str, state: &State) {
+    for workflow in workflows.values_mut() {
+        let states = workflow
+            .rules
+            .iter_mut()
+            .map(|rule| &mut rule.result)
+            .chain([&mut workflow.default_state]);
+
+        for s in states {
+            if *s == State::Passed(name.to_string()) {
+                *s = state.clone();
             }
+        }
+    }
+}
 
-            if let State::Passed(new_state) = &part.state {
-                if old_state == *new_state {
-                    // the state has not changed, so none of the rules applied,
-                    // hence, we fall back to the default rule
-                    part.state = state_clone(&workflow.default_state);
+/// Rewrites the workflows into an equivalent smaller set, which accepts exactly the same
+/// parts. The workflows must be free of the fatal findings of `analyse_workflows`.
+fn minimise_workflows(
+    mut workflows: HashMap<String, Workflow>,
+    domains: &[(u32, u32)],
+) -> HashMap<String, Workflow> {
+    // every rewrite may open up others, so keep going until nothing changes anymore. The
+    // analysis is the expensive part, so it runs once per pass and everything it found is
+    // rewritten in that pass, which is fine since no rewrite changes the accepted parts.
+    loop {
+        let findings = analyse_workflows(&workflows, domains);
+        let mut changed = false;
+
+        // drop the rules that can never fire, last ones first to keep the indices valid
+        let mut dead: Vec<(&String, usize)> = findings
+            .iter()
+            .filter_map(|f| match f {
+                Finding::DeadRule { workflow, rule } => Some((workflow, *rule)),
+                _ => None,
+            })
+            .collect();
+        dead.sort();
+        for (workflow, rule) in dead.into_iter().rev() {
+            workflows.get_mut(workflow).unwrap().rules.remove(rule);
+            changed = true;
+        }
+
+        // collapse the workflows that always end up accepted or rejected
+        for finding in &findings {
+            let Finding::ConstantOutcome {
+                workflow: name,
+                state,
+            } = finding
+            else {
+                continue;
+            };
+
+            if name == "in" {
+                let wf = workflows.get_mut(name).unwrap();
+                if !wf.rules.is_empty() || wf.default_state != *state {
+                    wf.rules.clear();
+                    wf.default_state = state.clone();
+                    changed = true;
                 }
+            } else if workflows.remove(name).is_some() {
+                replace_target(&mut workflows, name, state);
+                changed = true;
             }
+        }
 
-            // push the current part back into the vec
-            possible_parts.push(part);
-        } else if let State::Accepted = &part.state {
-            //println!("{:#?}", part);
-            // this is an accepted part, so we are done with it
-            // count all the part combinations by multiplication
-            let mut part_combination = (part.x.1 as u64 - part.x.0 as u64) + 1;
-            part_combination *= (part.m.1 as u64 - part.m.0 as u64) + 1;
-            part_combination *= (part.a.1 as u64 - part.a.0 as u64) + 1;
-            part_combination *= (part.s.1 as u64 - part.s.0 as u64) + 1;
-
-            if nrof_combinations == 0 {
-                nrof_combinations = part_combination;
-            } else {
-                nrof_combinations += part_combination;
+        for finding in &findings {
+            if let Finding::Unreachable(name) = finding {
+                changed |= workflows.remove(name).is_some();
             }
         }
+
+        changed |= simplify_workflows(&mut workflows);
+
+        if !changed {
+            return workflows;
+        }
     }
+}
+
+/// The rewrites of `minimise_workflows` that follow from the shape of the workflows alone,
+/// repeated until none applies anymore. Returns whether anything was rewritten.
+fn simplify_workflows(workflows: &mut HashMap<String, Workflow>) -> bool {
+    let mut simplified = false;
+
+    loop {
+        let mut names: Vec<String> = workflows.keys().cloned().collect();
+        names.sort();
+
+        let mut changed = false;
 
-    nrof_combinations
+        // a rule sending parts to the same place as the default right after it is redundant
+        for name in &names {
+            let wf = workflows.get_mut(name).unwrap();
+            while wf
+                .rules
+                .last()
+                .is_some_and(|rule| rule.result == wf.default_state)
+            {
+                wf.rules.pop();
+                changed = true;
+            }
+        }
+        if changed {
+            simplified = true;
+            continue;
+        }
+
+        // a workflow without rules just passes parts on to its default
+        if let Some(name) = names
+            .iter()
+            .find(|&n| n != "in" && workflows[n].rules.is_empty())
+        {
+            let wf = workflows.remove(name).unwrap();
+            replace_target(workflows, name, &wf.default_state);
+            simplified = true;
+            continue;
+        }
+
+        // inline workflows that are only used as the default of a single other workflow,
+        // as their rules simply follow the rules of that workflow
+        let mut uses: HashMap<&String, usize> = HashMap::new();
+        for wf in workflows.values() {
+            for target in workflow_targets(wf) {
+                *uses.entry(target).or_insert(0) += 1;
+            }
+        }
+
+        let inlinable = names
+            .iter()
+            .find_map(|name| match &workflows[name].default_state {
+                State::Passed(target) if target != "in" && uses[target] == 1 => {
+                    Some((name.clone(), target.clone()))
+                }
+                _ => None,
+            });
+        if let Some((name, target)) = inlinable {
+            let inlined = workflows.remove(&target).unwrap();
+            let wf = workflows.get_mut(&name).unwrap();
+            wf.rules.extend(inlined.rules);
+            wf.default_state = inlined.default_state;
+            simplified = true;
+            continue;
+        }
+
+        return simplified;
+    }
 }
 
 fn main() {
-    let workflows = parse_input("./example.input");
+    let args: Vec<String> = env::args().collect();
+    let input = match Input::from_args(&args) {
+        Ok(input) => input,
+        Err(e) => {
+            eprintln!("Can't pick the input, {}", e);
+            return;
+        }
+    };
+    let lines = match input.read_lines() {
+        Ok(lines) => lines,
+        Err(e) => {
+            eprintln!("Can't read {}, {}", input, e);
+            return;
+        }
+    };
+    let (workflows, attributes) = parse_input(lines);
+    let domains = match parse_domains(&args, &attributes) {
+        Ok(domains) => domains,
+        Err(e) => {
+            eprintln!("Can't use the domain, {}", e);
+            return;
+        }
+    };
 
-    let nrof = get_possible_distinct_combinations(workflows);
+    let findings = analyse_workflows(&workflows, &domains);
+
+    if args.iter().any(|a| a == "--analyse") {
+        for finding in &findings {
+            println!("{}", finding);
+        }
+        return;
+    }
+
+    // undefined workflows would make the evaluation panic and cycles would make it loop forever
+    let fatal: Vec<&Finding> = findings.iter().filter(|f| f.is_fatal()).collect();
+    if !fatal.is_empty() {
+        for finding in fatal {
+            println!("Can't evaluate the workflows, {}", finding);
+        }
+        return;
+    }
+
+    if args.iter().any(|a| a == "--minimise") {
+        let minimised = minimise_workflows(workflows, &domains);
+
+        let mut names: Vec<&String> = minimised.keys().collect();
+        names.sort();
+        for name in names {
+            println!("{}", format_workflow(&minimised[name], &attributes));
+        }
+        return;
+    }
+
+    if let Some(i) = args.iter().position(|a| a == "--boxes") {
+        let boxes = get_accepted_boxes(&workflows, &domains);
+
+        // the format is optional, so a following option means the default of CSV
+        match args.get(i + 1).map(|f| f.as_str()) {
+            Some("json") => print!("{}", boxes_to_json(&boxes, &attributes)),
+            Some("csv") | None => print!("{}", boxes_to_csv(&boxes, &attributes)),
+            Some(option) if option.starts_with('-') => {
+                print!("{}", boxes_to_csv(&boxes, &attributes))
+            }
+            Some(format) => eprintln!("Can't export the boxes, {} isn't csv or json", format),
+        }
+        return;
+    }
+
+    if let Some(i) = args.iter().position(|a| a == "--explain") {
+        let part_raw = args.get(i + 1).map_or("", |s| s.as_str());
+        let ratings = match parse_part(part_raw, &attributes, &domains) {
+            Ok(ratings) => ratings,
+            Err(e) => {
+                eprintln!("Can't explain the part, {}", e);
+                return;
+            }
+        };
+
+        let boxes = get_accepted_boxes(&workflows, &domains);
+        let found = boxes.iter().find(|part| {
+            part.ranges
+                .iter()
+                .zip(&ratings)
+                .all(|(range, &r)| range.0 <= r && r <= range.1)
+        });
+
+        match found {
+            Some(part) => println!(
+                "Accepted via {}, in box {:?}",
+                part.path.join(" -> "),
+                part.ranges
+            ),
+            None => println!("Rejected"),
+        }
+        return;
+    }
+
+    let nrof = get_possible_distinct_combinations(workflows, &domains);
 
     println!("Nrof distinct possible combinations is {}", nrof);
 }
@@ -298,6 +1032,7 @@ fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aoc::xorshift::Xorshift;
 
     #[test]
     fn test_simple() {
@@ -311,8 +1046,688 @@ mod tests {
 
         flows.insert(String::from("in"), wf);
 
-        let nrof = get_possible_distinct_combinations(flows);
+        let attributes: Vec<String> = ["x", "m", "a", "s"].map(String::from).to_vec();
+
+        let nrof =
+            get_possible_distinct_combinations(flows, &parse_domains(&[], &attributes).unwrap());
+
+        assert_eq!(nrof, u128::pow(4000, 4));
+    }
+
+    fn workflows_of(raw_workflows: &[&str]) -> (HashMap<String, Workflow>, Vec<String>) {
+        let mut flows: HashMap<String, Workflow> = HashMap::new();
+        let mut attributes: Vec<String> = ["x", "m", "a", "s"].map(String::from).to_vec();
+
+        for raw in raw_workflows {
+            let wf = parse_workflow(String::from(*raw), &mut attributes);
+            flows.insert(wf.name.clone(), wf);
+        }
+
+        (flows, attributes)
+    }
+
+    fn combinations_of(raw_workflows: &[&str]) -> u128 {
+        let (flows, attributes) = workflows_of(raw_workflows);
+
+        get_possible_distinct_combinations(flows, &parse_domains(&[], &attributes).unwrap())
+    }
+
+    #[test]
+    fn test_split_range() {
+        assert_eq!(
+            split_range((1, 10), &Op::Lt, 4),
+            (vec![(1, 3)], vec![(4, 10)])
+        );
+        assert_eq!(
+            split_range((1, 10), &Op::Le, 4),
+            (vec![(1, 4)], vec![(5, 10)])
+        );
+        assert_eq!(
+            split_range((1, 10), &Op::Gt, 4),
+            (vec![(5, 10)], vec![(1, 4)])
+        );
+        assert_eq!(
+            split_range((1, 10), &Op::Ge, 4),
+            (vec![(4, 10)], vec![(1, 3)])
+        );
+        assert_eq!(
+            split_range((1, 10), &Op::Eq, 4),
+            (vec![(4, 4)], vec![(1, 3), (5, 10)])
+        );
+        assert_eq!(
+            split_range((1, 10), &Op::Ne, 4),
+            (vec![(1, 3), (5, 10)], vec![(4, 4)])
+        );
+        assert_eq!(split_range((1, 10), &Op::Eq, 11), (vec![], vec![(1, 10)]));
+        assert_eq!(split_range((1, 10), &Op::Lt, 0), (vec![], vec![(1, 10)]));
+    }
 
-        assert_eq!(nrof, u64::pow(4000, 4));
+    #[test]
+    fn test_inclusive_operators() {
+        let rest = u128::pow(4000, 3);
+
+        assert_eq!(combinations_of(&["in{x<=10:A,R}"]), 10 * rest);
+        assert_eq!(combinations_of(&["in{x>=3991:A,R}"]), 10 * rest);
+        assert_eq!(combinations_of(&["in{x<10:A,R}"]), 9 * rest);
+        assert_eq!(combinations_of(&["in{x>3991:A,R}"]), 9 * rest);
+    }
+
+    #[test]
+    fn test_equality_operators() {
+        let rest = u128::pow(4000, 3);
+
+        assert_eq!(combinations_of(&["in{m==5:A,R}"]), rest);
+        assert_eq!(combinations_of(&["in{s!=5:A,R}"]), 3999 * rest);
+        assert_eq!(
+            combinations_of(&["in{x!=1:ne,R}", "ne{m==2:A,R}"]),
+            3999 * u128::pow(4000, 2)
+        );
+    }
+
+    #[test]
+    fn test_example_attributes() {
+        let (workflows, attributes) = parse_input(Input::example(1).read_lines().unwrap());
+
+        assert_eq!(attributes, vec!["a", "m", "s", "x"]);
+        assert_eq!(
+            get_possible_distinct_combinations(
+                workflows,
+                &parse_domains(&[], &attributes).unwrap()
+            ),
+            167409079868000
+        );
+    }
+
+    #[test]
+    fn test_custom_attributes() {
+        let mut flows: HashMap<String, Workflow> = HashMap::new();
+        let mut attributes: Vec<String> = Vec::new();
+
+        for raw in ["in{weight>3000:heavy,R}", "heavy{colour<=10:A,R}"] {
+            let wf = parse_workflow(String::from(raw), &mut attributes);
+            flows.insert(wf.name.clone(), wf);
+        }
+        attributes.push(String::from("size"));
+
+        assert_eq!(
+            get_possible_distinct_combinations(flows, &parse_domains(&[], &attributes).unwrap()),
+            1000 * 10 * 4000
+        );
+    }
+
+    #[test]
+    fn test_analyse_example() {
+        let (workflows, attributes) = parse_input(Input::example(1).read_lines().unwrap());
+
+        let findings = analyse_workflows(&workflows, &parse_domains(&[], &attributes).unwrap());
+
+        assert_eq!(
+            findings,
+            vec![
+                Finding::ConstantOutcome {
+                    workflow: String::from("gd"),
+                    state: State::Rejected
+                },
+                Finding::ConstantOutcome {
+                    workflow: String::from("lnx"),
+                    state: State::Accepted
+                },
+                Finding::ConstantOutcome {
+                    workflow: String::from("qs"),
+                    state: State::Accepted
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_analyse_graph_problems() {
+        let (workflows, attributes) = workflows_of(&[
+            "in{x<10:a,m>5:missing,R}",
+            "a{s<100:b,R}",
+            "b{a>5:a,A}",
+            "lost{x<5:A,R}",
+        ]);
+
+        let findings = analyse_workflows(&workflows, &parse_domains(&[], &attributes).unwrap());
+
+        assert_eq!(
+            findings,
+            vec![
+                Finding::UndefinedTarget {
+                    workflow: String::from("in"),
+                    target: String::from("missing")
+                },
+                Finding::Unreachable(String::from("lost")),
+                Finding::Cycle(vec![
+                    String::from("a"),
+                    String::from("b"),
+                    String::from("a")
+                ]),
+            ]
+        );
+        assert_eq!(findings.iter().filter(|f| f.is_fatal()).count(), 2);
+    }
+
+    #[test]
+    fn test_analyse_missing_start() {
+        let (workflows, attributes) = workflows_of(&["px{x<10:A,R}"]);
+
+        let findings = analyse_workflows(&workflows, &parse_domains(&[], &attributes).unwrap());
+
+        assert_eq!(findings[0], Finding::MissingStart);
+        assert_eq!(
+            findings[0].to_string(),
+            "there is no workflow `in` to start from"
+        );
+    }
+
+    #[test]
+    fn test_analyse_dead_rules() {
+        let (workflows, attributes) =
+            workflows_of(&["in{x<10:px,x<5:R,m>5:px,m<=5:R,a<3:A,A}", "px{x>0:A,R}"]);
+
+        let findings = analyse_workflows(&workflows, &parse_domains(&[], &attributes).unwrap());
+
+        assert_eq!(
+            findings,
+            vec![
+                Finding::DeadRule {
+                    workflow: String::from("in"),
+                    rule: 1
+                },
+                Finding::DeadRule {
+                    workflow: String::from("in"),
+                    rule: 4
+                },
+                Finding::ConstantOutcome {
+                    workflow: String::from("px"),
+                    state: State::Accepted
+                },
+            ]
+        );
+        assert_eq!(
+            findings[0].to_string(),
+            "rule 2 of workflow `in` can never fire"
+        );
+    }
+
+    fn is_accepted(workflows: &HashMap<String, Workflow>, ratings: &[u32]) -> bool {
+        let mut state = State::Passed(String::from("in"));
+
+        while let State::Passed(name) = &state {
+            let workflow = &workflows[name];
+
+            state = workflow
+                .rules
+                .iter()
+                .find(|rule| {
+                    let (value, bound) = (ratings[rule.prop], rule.value);
+                    match rule.op {
+                        Op::Lt => value < bound,
+                        Op::Gt => value > bound,
+                        Op::Le => value <= bound,
+                        Op::Ge => value >= bound,
+                        Op::Eq => value == bound,
+                        Op::Ne => value != bound,
+                    }
+                })
+                .map_or(&workflow.default_state, |rule| &rule.result)
+                .clone();
+        }
+
+        state == State::Accepted
+    }
+
+    /// Asserts both sets of workflows accept the same parts, by checking one part out of every
+    /// cell of the grid the rule values of both cut the attribute domains into.
+    fn assert_equivalent(
+        a: &HashMap<String, Workflow>,
+        b: &HashMap<String, Workflow>,
+        attributes: &[String],
+    ) {
+        let mut cuts: Vec<Vec<u32>> = vec![vec![1, 4001]; attributes.len()];
+        for rule in a.values().chain(b.values()).flat_map(|wf| &wf.rules) {
+            cuts[rule.prop].extend([rule.value, rule.value + 1]);
+        }
+        for c in cuts.iter_mut() {
+            c.retain(|&v| (1..=4001).contains(&v));
+            c.sort();
+            c.dedup();
+        }
+
+        let mut cell = vec![0; attributes.len()];
+        loop {
+            let ratings: Vec<u32> = cell.iter().enumerate().map(|(i, &c)| cuts[i][c]).collect();
+            assert_eq!(
+                is_accepted(a, &ratings),
+                is_accepted(b, &ratings),
+                "workflows differ for ratings {:?}",
+                ratings
+            );
+
+            // move on to the next cell, like counting with a digit per attribute
+            let mut i = 0;
+            while i < cell.len() && cell[i] + 2 == cuts[i].len() {
+                cell[i] = 0;
+                i += 1;
+            }
+            if i == cell.len() {
+                break;
+            }
+            cell[i] += 1;
+        }
+    }
+
+    fn format_all(workflows: &HashMap<String, Workflow>, attributes: &[String]) -> Vec<String> {
+        let mut formatted: Vec<String> = workflows
+            .values()
+            .map(|wf| format_workflow(wf, attributes))
+            .collect();
+        formatted.sort();
+
+        formatted
+    }
+
+    #[test]
+    fn test_format_workflow_round_trips() {
+        let raw = "px{a<2006:qkq,m>=2090:A,s!=4:R,rfg}";
+        let mut attributes: Vec<String> = Vec::new();
+
+        let wf = parse_workflow(String::from(raw), &mut attributes);
+
+        assert_eq!(format_workflow(&wf, &attributes), raw);
+        assert_eq!(
+            format_workflow(
+                &parse_workflow(String::from("in{A}"), &mut attributes),
+                &attributes
+            ),
+            "in{A}"
+        );
+    }
+
+    #[test]
+    fn test_minimise_example() {
+        let (workflows, attributes) = parse_input(Input::example(1).read_lines().unwrap());
+        let (original, _) = parse_input(Input::example(1).read_lines().unwrap());
+
+        let minimised = minimise_workflows(workflows, &parse_domains(&[], &attributes).unwrap());
+
+        assert_equivalent(&original, &minimised, &attributes);
+        assert!(minimised.len() < original.len());
+        assert_eq!(
+            format_all(&minimised, &attributes),
+            vec![
+                "hdj{m>838:A,a>1716:R,A}",
+                "in{s<1351:px,s>2770:A,m<1801:hdj,R}",
+                "px{a<2006:qkq,m>2090:A,s<537:R,x>2440:R,A}",
+                "qkq{x<1416:A,x>2662:A,R}",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_minimise_rewrites() {
+        let (workflows, attributes) = workflows_of(&[
+            "in{x<10:a,x<5:R,m>100:b,c}",
+            "a{s<5:A,A}",
+            "b{m>50:R,a>10:R,R}",
+            "c{a<100:d,R}",
+            "d{s>20:A,x>3000:R,R}",
+            "unused{x<3:A,R}",
+        ]);
+        let (original, _) = workflows_of(&[
+            "in{x<10:a,x<5:R,m>100:b,c}",
+            "a{s<5:A,A}",
+            "b{m>50:R,a>10:R,R}",
+            "c{a<100:d,R}",
+            "d{s>20:A,x>3000:R,R}",
+            "unused{x<3:A,R}",
+        ]);
+
+        let minimised = minimise_workflows(workflows, &parse_domains(&[], &attributes).unwrap());
+
+        assert_equivalent(&original, &minimised, &attributes);
+        assert_eq!(
+            format_all(&minimised, &attributes),
+            vec!["d{s>20:A,R}", "in{x<10:A,m>100:R,a<100:d,R}"]
+        );
+    }
+
+    #[test]
+    fn test_minimise_constant_start() {
+        let (workflows, attributes) = workflows_of(&["in{x<10:a,R}", "a{m>5:R,R}"]);
+
+        let minimised = minimise_workflows(workflows, &parse_domains(&[], &attributes).unwrap());
+
+        assert_eq!(format_all(&minimised, &attributes), vec!["in{R}"]);
+    }
+
+    #[test]
+    fn test_accepted_boxes() {
+        let (workflows, attributes) = workflows_of(&["in{x<10:a,R}", "a{m>3000:A,R}"]);
+
+        let boxes = get_accepted_boxes(&workflows, &parse_domains(&[], &attributes).unwrap());
+
+        assert_eq!(boxes.len(), 1);
+        assert_eq!(boxes[0].path, vec!["in", "a"]);
+        assert_eq!(
+            boxes[0].ranges,
+            vec![(1, 9), (3001, 4000), (1, 4000), (1, 4000)]
+        );
+        assert_eq!(boxes[0].combinations(), 9 * 1000 * 4000 * 4000);
+    }
+
+    #[test]
+    fn test_accepted_boxes_are_disjoint() {
+        let (workflows, attributes) = parse_input(Input::example(1).read_lines().unwrap());
+
+        let boxes = get_accepted_boxes(&workflows, &parse_domains(&[], &attributes).unwrap());
+
+        for (i, a) in boxes.iter().enumerate() {
+            assert_eq!(a.path.first().unwrap(), "in");
+            for b in &boxes[i + 1..] {
+                let overlaps = a
+                    .ranges
+                    .iter()
+                    .zip(&b.ranges)
+                    .all(|(ra, rb)| ra.0 <= rb.1 && rb.0 <= ra.1);
+                assert!(!overlaps, "{:?} overlaps {:?}", a, b);
+            }
+        }
+        assert_eq!(
+            boxes.iter().map(|b| b.combinations()).sum::<u128>(),
+            167409079868000
+        );
+    }
+
+    #[test]
+    fn test_export_boxes() {
+        let (workflows, attributes) = workflows_of(&["in{x<10:a,R}", "a{m>3000:A,R}"]);
+
+        let boxes = get_accepted_boxes(&workflows, &parse_domains(&[], &attributes).unwrap());
+
+        assert_eq!(
+            boxes_to_csv(&boxes, &attributes),
+            "path,x_from,x_to,m_from,m_to,a_from,a_to,s_from,s_to,combinations\n\
+             in>a,1,9,3001,4000,1,4000,1,4000,144000000000\n"
+        );
+        assert_eq!(
+            boxes_to_json(&boxes, &attributes),
+            "[\n  {\"path\":[\"in\",\"a\"],\"ranges\":{\"x\":[1,9],\"m\":[3001,4000],\
+             \"a\":[1,4000],\"s\":[1,4000]},\"combinations\":144000000000}\n]\n"
+        );
+    }
+
+    #[test]
+    fn test_parse_domains() {
+        let attributes: Vec<String> = ["x", "m"].map(String::from).to_vec();
+        let args: Vec<String> = ["--domain", "m=0-65535"].map(String::from).to_vec();
+
+        assert_eq!(
+            parse_domains(&args, &attributes),
+            Ok(vec![(1, 4000), (0, 65535)])
+        );
+    }
+
+    #[test]
+    fn test_parse_domains_errors() {
+        let attributes: Vec<String> = ["x", "m"].map(String::from).to_vec();
+        let domains_of = |args: &[&str]| {
+            let args: Vec<String> = args.iter().map(|a| String::from(*a)).collect();
+            parse_domains(&args, &attributes)
+        };
+
+        assert_eq!(
+            domains_of(&["--domain", "x=10-5"]),
+            Err(DomainError::Empty(String::from("x=10-5")))
+        );
+        assert_eq!(
+            domains_of(&["--domain", "s=1-5"]),
+            Err(DomainError::UnknownAttribute(String::from("s")))
+        );
+        assert_eq!(
+            domains_of(&["--domain", "x=1-99999999999"]),
+            Err(DomainError::Malformed(String::from("x=1-99999999999")))
+        );
+        assert_eq!(
+            domains_of(&["--domain"]),
+            Err(DomainError::Malformed(String::new()))
+        );
+        assert_eq!(
+            domains_of(&["--domain", "x=5-5"]),
+            Ok(vec![(5, 5), (1, 4000)])
+        );
+    }
+
+    #[test]
+    fn test_parse_domains_too_large() {
+        let attributes: Vec<String> = ["x", "m", "a", "s"].map(String::from).to_vec();
+        let full = ["x", "m", "a", "s"].map(|a| format!("{}=0-4294967295", a));
+        let args: Vec<String> = full
+            .iter()
+            .flat_map(|d| [String::from("--domain"), d.clone()])
+            .collect();
+
+        // 2^128 parts are one too many
+        assert_eq!(
+            parse_domains(&args, &attributes),
+            Err(DomainError::TooLarge)
+        );
+        assert_eq!(
+            parse_domains(&args[..6], &attributes),
+            Ok(vec![(0, u32::MAX), (0, u32::MAX), (0, u32::MAX), (1, 4000)])
+        );
+
+        // the combinations of the largest domains that are accepted still fit
+        let mut domains = parse_domains(&args[..6], &attributes).unwrap();
+        domains[3] = (1, u32::MAX);
+        let (workflows, _) = workflows_of(&["in{A}"]);
+        assert_eq!(
+            get_possible_distinct_combinations(workflows, &domains),
+            (1 << 96) * u128::from(u32::MAX)
+        );
+    }
+
+    #[test]
+    fn test_parse_part() {
+        let attributes: Vec<String> = ["x", "m"].iter().map(|a| a.to_string()).collect();
+        let domains = vec![DEFAULT_DOMAIN, (0, 10)];
+
+        assert_eq!(
+            parse_part("{m=3,x=787}", &attributes, &domains),
+            Ok(vec![787, 3])
+        );
+        assert_eq!(
+            parse_part("{x=787}", &attributes, &domains),
+            Err(PartError::Missing {
+                part: String::from("{x=787}"),
+                attribute: String::from("m")
+            })
+        );
+        assert_eq!(
+            parse_part("{x=787,m=11}", &attributes, &domains),
+            Err(PartError::OutOfDomain {
+                part: String::from("{x=787,m=11}"),
+                attribute: String::from("m")
+            })
+        );
+        assert_eq!(
+            parse_part("{x=787,m=3,s=1}", &attributes, &domains),
+            Err(PartError::UnknownAttribute {
+                part: String::from("{x=787,m=3,s=1}"),
+                attribute: String::from("s")
+            })
+        );
+        assert_eq!(
+            parse_part("{x=99999999999,m=3}", &attributes, &domains),
+            Err(PartError::Malformed(String::from("{x=99999999999,m=3}")))
+        );
+        assert_eq!(
+            parse_part("", &attributes, &domains),
+            Err(PartError::Malformed(String::new()))
+        );
+    }
+
+    #[test]
+    fn test_full_u32_domains() {
+        let (workflows, attributes) = workflows_of(&["in{A}"]);
+        let domains = vec![(1, u32::MAX); attributes.len()];
+
+        assert_eq!(
+            get_possible_distinct_combinations(workflows, &domains),
+            u128::from(u32::MAX).pow(4)
+        );
+
+        let (workflows, attributes) = workflows_of(&["in{x<=2147483647:A,m>4294967294:A,R}"]);
+        let domains = vec![(1, u32::MAX); attributes.len()];
+
+        assert_eq!(
+            get_possible_distinct_combinations(workflows, &domains),
+            2147483647 * u128::from(u32::MAX).pow(3) + 2147483648 * u128::from(u32::MAX).pow(2)
+        );
+    }
+
+    #[test]
+    fn test_domains_per_attribute() {
+        let (workflows, _) = workflows_of(&["in{x>5:A,R}"]);
+        let domains = vec![(0, 9), (1, 2), (1, 3), (0, 0)];
+
+        assert_eq!(
+            get_possible_distinct_combinations(workflows, &domains),
+            4 * 2 * 3
+        );
+        assert_eq!(
+            analyse_workflows(&workflows_of(&["in{x>9:A,R}"]).0, &domains),
+            vec![
+                Finding::DeadRule {
+                    workflow: String::from("in"),
+                    rule: 0
+                },
+                Finding::ConstantOutcome {
+                    workflow: String::from("in"),
+                    state: State::Rejected
+                }
+            ]
+        );
+    }
+
+    /// Generates `count` random sets of workflows over the xmas attributes, with rule values
+    /// around `1..=max`. Workflows only send parts on to workflows after them, so there are
+    /// no cycles.
+    fn random_workflow_sets(count: usize, max: u32) -> Vec<Vec<String>> {
+        let mut rng = Xorshift::new();
+        let mut next = |bound: u64| rng.below(bound);
+
+        let ops = ["<", ">", "<=", ">=", "==", "!="];
+        let names: Vec<String> = (0..6)
+            .map(|i| match i {
+                0 => String::from("in"),
+                _ => format!("w{}", (b'a' + i as u8) as char),
+            })
+            .collect();
+
+        (0..count)
+            .map(|_| {
+                let nrof_workflows = 1 + next(names.len() as u64) as usize;
+
+                (0..nrof_workflows)
+                    .map(|i| {
+                        // any workflow after this one, or accepted or rejected
+                        let later = (nrof_workflows - i - 1) as u64;
+                        let target = |n: u64| -> String {
+                            match n {
+                                0 => String::from("A"),
+                                1 => String::from("R"),
+                                n => names[i + n as usize - 1].clone(),
+                            }
+                        };
+
+                        let mut flows: Vec<String> = (0..next(5))
+                            .map(|_| {
+                                format!(
+                                    "{}{}{}:{}",
+                                    ["x", "m", "a", "s"][next(4) as usize],
+                                    ops[next(6) as usize],
+                                    next(u64::from(max) + 2),
+                                    target(next(later + 2))
+                                )
+                            })
+                            .collect();
+                        flows.push(target(next(later + 2)));
+
+                        format!("{}{{{}}}", names[i], flows.join(","))
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_boxes_match_brute_force() {
+        let max: u32 = 20;
+
+        for raw_workflows in random_workflow_sets(25, max) {
+            let raw: Vec<&str> = raw_workflows.iter().map(|r| r.as_str()).collect();
+            let (workflows, attributes) = workflows_of(&raw);
+            let domains = vec![(1, max); attributes.len()];
+
+            let boxes = get_accepted_boxes(&workflows, &domains);
+
+            // count how many boxes each part is in, which is 1 for accepted parts and 0 for the
+            // rest if the boxes are right and disjoint
+            let mut nrof_accepted: u128 = 0;
+            let mut ratings = vec![1; attributes.len()];
+            loop {
+                let in_boxes = boxes
+                    .iter()
+                    .filter(|b| {
+                        b.ranges
+                            .iter()
+                            .zip(&ratings)
+                            .all(|(r, v)| r.0 <= *v && *v <= r.1)
+                    })
+                    .count();
+                let accepted = is_accepted(&workflows, &ratings);
+
+                assert_eq!(
+                    in_boxes,
+                    usize::from(accepted),
+                    "workflows {:?} differ for ratings {:?}",
+                    raw,
+                    ratings
+                );
+                nrof_accepted += u128::from(accepted);
+
+                let mut i = 0;
+                while i < ratings.len() && ratings[i] == max {
+                    ratings[i] = 1;
+                    i += 1;
+                }
+                if i == ratings.len() {
+                    break;
+                }
+                ratings[i] += 1;
+            }
+
+            assert_eq!(
+                get_possible_distinct_combinations(workflows, &domains),
+                nrof_accepted
+            );
+        }
+    }
+
+    #[test]
+    fn test_rules_to_accepted_rejected_and_same_name() {
+        // a rule that moves to A or R must stop the part from reaching later rules, also when
+        // the workflow it came from had a rule to the same name
+        assert_eq!(
+            combinations_of(&["in{x<2001:A,x<3001:R,m>2000:A,R}"]),
+            2000 * u128::pow(4000, 3) + 1000 * 2000 * u128::pow(4000, 2)
+        );
+        assert_eq!(
+            combinations_of(&["in{x<2001:px,x<3001:px,R}", "px{x<1001:R,A}"]),
+            2000 * u128::pow(4000, 3)
+        );
     }
 }
@@ -0,0 +1,825 @@
+use std::collections::HashMap;
+use std::time::Instant;
+use std::{env, fmt};
+
+use aoc::{xorshift::Xorshift, Input};
+use regex::Regex;
+
+/// A scratchcard, with both sets of numbers as a bitset in which bit `n` is set when `n` is
+/// on the card.
+struct Card {
+    id: usize,
+    winning: u128,
+    have: u128,
+}
+
+impl Card {
+    /// The number of winning numbers we have.
+    fn matches(&self) -> usize {
+        (self.winning & self.have).count_ones() as usize
+    }
+}
+
+/// How the number of matches on a card is turned into points.
+#[derive(Debug, PartialEq)]
+enum Scoring {
+    /// 1 point for the first match, doubled for every match after it.
+    Doubling,
+    /// 1 point per match.
+    Linear,
+    /// The n-th Fibonacci number for n matches, so 1, 1, 2, 3, 5 and so on.
+    Fibonacci,
+    /// The points per number of matches, starting at no matches. More matches than the table
+    /// goes up to score as many points as its last entry.
+    Table(Vec<u64>),
+}
+
+impl Scoring {
+    /// Parses `doubling`, `linear`, `fibonacci` or a table like `0,1,3,6`.
+    fn parse(raw: &str) -> Option<Scoring> {
+        match raw {
+            "doubling" => Some(Scoring::Doubling),
+            "linear" => Some(Scoring::Linear),
+            "fibonacci" => Some(Scoring::Fibonacci),
+            _ => {
+                let table: Vec<u64> = raw
+                    .split(',')
+                    .map(|p| p.trim().parse().ok())
+                    .collect::<Option<_>>()?;
+                Some(Scoring::Table(table))
+            }
+        }
+    }
+
+    /// The points for a card with `matches` matches, or `None` if they don't fit in a u64.
+    fn points(&self, matches: usize) -> Option<u64> {
+        match self {
+            Scoring::Doubling => match matches {
+                0 => Some(0),
+                finds => u32::try_from(finds - 1)
+                    .ok()
+                    .and_then(|shift| 1u64.checked_shl(shift)),
+            },
+            Scoring::Linear => u64::try_from(matches).ok(),
+            Scoring::Fibonacci => {
+                if matches == 0 {
+                    return Some(0);
+                }
+                // stop at the points themselves, as the number after them may not fit while they do
+                let (mut a, mut b): (u64, u64) = (0, 1);
+                for _ in 1..matches {
+                    (a, b) = (b, a.checked_add(b)?);
+                }
+                Some(b)
+            }
+            Scoring::Table(table) => {
+                Some(table.get(matches).or(table.last()).copied().unwrap_or(0))
+            }
+        }
+    }
+}
+
+/// Why the cards can't be used, with the id of the card at fault.
+#[derive(Debug, PartialEq)]
+enum CardError {
+    /// The line isn't like `Card 1: 41 48 | 83 86`.
+    Malformed(String),
+    /// The number doesn't fit in the bitsets, which hold 0 up to 127.
+    NumberOutOfRange { card: usize, number: u32 },
+    /// The number is on the same side of the card twice.
+    DuplicateNumber { card: usize, number: u32 },
+    /// The card doesn't have the id after the one of the card before it, where the first
+    /// card follows a card 0.
+    Gap { previous: usize, card: usize },
+    /// The card wins copies of cards past the end of the table.
+    PastEnd { card: usize, matches: usize },
+    /// The copies of the card, or the copies it wins, don't fit in a u64.
+    TooManyCopies { card: usize },
+    /// All copies together don't fit in a u64.
+    TooManyCards,
+    /// The points of the card don't fit in a u64.
+    TooManyPoints { card: usize },
+    /// The points of all cards together don't fit in a u64.
+    TooManyPointsInTotal,
+}
+
+impl fmt::Display for CardError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CardError::Malformed(line) => write!(f, "\"{}\" isn't a card", line),
+            CardError::NumberOutOfRange { card, number } => {
+                write!(f, "card {} has {}, which is over 127", card, number)
+            }
+            CardError::DuplicateNumber { card, number } => {
+                write!(f, "card {} has {} twice on the same side", card, number)
+            }
+            CardError::Gap { previous: 0, card } => {
+                write!(f, "card {} comes first instead of card 1", card)
+            }
+            CardError::Gap { previous, card } => {
+                write!(f, "card {} follows card {}", card, previous)
+            }
+            CardError::PastEnd { card, matches } => write!(
+                f,
+                "card {} wins copies of the {} cards after it, which are past the end",
+                card, matches
+            ),
+            CardError::TooManyCopies { card } => {
+                write!(f, "card {} adds up to more copies than fit in a u64", card)
+            }
+            CardError::TooManyCards => write!(f, "there are more cards than fit in a u64"),
+            CardError::TooManyPoints { card } => {
+                write!(f, "card {} scores more points than fit in a u64", card)
+            }
+            CardError::TooManyPointsInTotal => {
+                write!(f, "the cards score more points than fit in a u64")
+            }
+        }
+    }
+}
+
+/// What to do with wins of copies of cards past the end of the table.
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum PastEnd {
+    /// Only copy the cards up to the end of the table.
+    Clamp,
+    /// Fail with `CardError::PastEnd`.
+    Error,
+}
+
+fn card_regex() -> Regex {
+    Regex::new(r"^Card\s+(\d+):([\d ]+)\|([\d ]+)$").unwrap()
+}
+
+fn parse_card(card_reg: &Regex, line: &str) -> Result<Card, CardError> {
+    let caps = card_reg
+        .captures(line)
+        .ok_or_else(|| CardError::Malformed(String::from(line)))?;
+    let id: usize = caps[1]
+        .parse()
+        .map_err(|_| CardError::Malformed(String::from(line)))?;
+
+    let to_bitset = |numbers: &str| -> Result<u128, CardError> {
+        let mut set: u128 = 0;
+
+        for n in numbers.split_whitespace() {
+            let number: u32 = n
+                .parse()
+                .map_err(|_| CardError::Malformed(String::from(line)))?;
+            let bit = 1u128
+                .checked_shl(number)
+                .ok_or(CardError::NumberOutOfRange { card: id, number })?;
+
+            if set & bit != 0 {
+                return Err(CardError::DuplicateNumber { card: id, number });
+            }
+            set |= bit;
+        }
+
+        Ok(set)
+    };
+
+    Ok(Card {
+        id,
+        winning: to_bitset(&caps[2])?,
+        have: to_bitset(&caps[3])?,
+    })
+}
+
+/// Parses all cards, which have to be numbered consecutively from 1, so that card n is the
+/// n-th card.
+fn parse_cards(lines: impl Iterator<Item = String>) -> Result<Vec<Card>, CardError> {
+    let card_reg = card_regex();
+    let mut cards: Vec<Card> = Vec::new();
+
+    for line in lines {
+        let card = parse_card(&card_reg, &line)?;
+
+        let previous = cards.last().map_or(0, |c| c.id);
+        if card.id != previous + 1 {
+            return Err(CardError::Gap {
+                previous,
+                card: card.id,
+            });
+        }
+
+        cards.push(card);
+    }
+
+    Ok(cards)
+}
+
+/// The number of wins on a card, by compiling the regexes and building a map of the winning
+/// numbers for every card.
+fn get_card_wins(line: &str) -> usize {
+    let card_reg = Regex::new(r"Card[\s\d]+: ([\d ]+)\| ([\d ]+)").unwrap();
+    let num_reg = Regex::new(r"[\d]+").unwrap();
+
+    let caps = card_reg.captures(line).unwrap();
+
+    // we use a hashmap for constant time lookup for all our scratched numbers
+    // so map the winning numbers into that hashmap
+    let wins: HashMap<&str, bool> = num_reg
+        .find_iter(caps.get(1).unwrap().as_str())
+        .map(|m| (m.as_str(), true))
+        .collect();
+
+    let mut finds = 0;
+
+    // and then do a lookup on the scratched numbers
+    for scratched in num_reg.find_iter(caps.get(2).unwrap().as_str()) {
+        if wins.contains_key(scratched.as_str()) {
+            finds += 1;
+        }
+    }
+
+    finds
+}
+
+/// The number of copies we end up with of every card. Walking the cards front to back, the
+/// copies of a card are final once we get to it, since only earlier cards win copies of it.
+fn count_copies(cards: &[Card], past_end: PastEnd) -> Result<Vec<u64>, CardError> {
+    let mut copies: Vec<u64> = vec![1; cards.len()];
+
+    for (i, card) in cards.iter().enumerate() {
+        if past_end == PastEnd::Error && i + card.matches() >= cards.len() {
+            return Err(CardError::PastEnd {
+                card: card.id,
+                matches: card.matches(),
+            });
+        }
+
+        // every copy of this card wins one copy of each of the next cards
+        let won = (i + 1 + card.matches()).min(cards.len());
+
+        for j in i + 1..won {
+            copies[j] = copies[j]
+                .checked_add(copies[i])
+                .ok_or(CardError::TooManyCopies { card: cards[j].id })?;
+        }
+    }
+
+    Ok(copies)
+}
+
+/// The number of cards a single copy of every card wins, directly and through the copies it
+/// wins. Walking back to front, this is final for all cards after a card once we get to it.
+fn downstream_per_copy(cards: &[Card]) -> Result<Vec<u64>, CardError> {
+    let mut downstream: Vec<u64> = vec![0; cards.len()];
+
+    for (i, card) in cards.iter().enumerate().rev() {
+        let won = (i + 1 + card.matches()).min(cards.len());
+
+        downstream[i] = (i + 1..won)
+            .try_fold(0, |sum: u64, j| {
+                sum.checked_add(downstream[j])?.checked_add(1)
+            })
+            .ok_or(CardError::TooManyCopies { card: card.id })?;
+    }
+
+    Ok(downstream)
+}
+
+/// How many copies every card adds to the total through its wins. Every copy of a card wins
+/// the same cards downstream, so without the card's wins exactly those would be missing.
+fn contributions(cards: &[Card], copies: &[u64]) -> Result<Vec<u64>, CardError> {
+    let downstream = downstream_per_copy(cards)?;
+
+    cards
+        .iter()
+        .zip(copies.iter().zip(&downstream))
+        .map(|(card, (c, d))| {
+            c.checked_mul(*d)
+                .ok_or(CardError::TooManyCopies { card: card.id })
+        })
+        .collect()
+}
+
+fn total_copies(copies: &[u64]) -> Result<u64, CardError> {
+    copies
+        .iter()
+        .try_fold(0, |sum: u64, &c| sum.checked_add(c))
+        .ok_or(CardError::TooManyCards)
+}
+
+fn total_points(cards: &[Card], scoring: &Scoring) -> Result<u64, CardError> {
+    cards.iter().try_fold(0, |sum: u64, card| {
+        let points = scoring
+            .points(card.matches())
+            .ok_or(CardError::TooManyPoints { card: card.id })?;
+
+        sum.checked_add(points)
+            .ok_or(CardError::TooManyPointsInTotal)
+    })
+}
+
+/// Cards with 10 winning numbers and 25 numbers we have, all below 100, like the puzzle's.
+fn generate_lines(count: usize) -> Vec<String> {
+    let mut rng = Xorshift::new();
+    let mut next = || rng.below(99) + 1;
+
+    (0..count)
+        .map(|i| {
+            // no number twice on the same side, as on the puzzle's cards
+            let mut numbers = |n: usize| -> String {
+                let mut picked: Vec<u64> = Vec::new();
+                while picked.len() < n {
+                    let number = next();
+                    if !picked.contains(&number) {
+                        picked.push(number);
+                    }
+                }
+
+                picked
+                    .iter()
+                    .map(|number| format!("{:2}", number))
+                    .collect::<Vec<String>>()
+                    .join(" ")
+            };
+
+            format!("Card {}: {} | {}", i + 1, numbers(10), numbers(25))
+        })
+        .collect()
+}
+
+fn run_benchmark(count: usize) {
+    let lines = generate_lines(count);
+
+    let now = Instant::now();
+    let wins: usize = lines.iter().map(|l| get_card_wins(l)).sum();
+    println!("Regex per card: {} in {:?}", wins, now.elapsed());
+
+    let now = Instant::now();
+    let cards = parse_cards(lines.into_iter()).unwrap();
+    let wins: usize = cards.iter().map(|c| c.matches()).sum();
+    println!("Bitsets:        {} in {:?}", wins, now.elapsed());
+}
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+
+    if let Some(i) = args.iter().position(|a| a == "--bench") {
+        match args.get(i + 1).and_then(|a| a.parse().ok()) {
+            Some(count) => run_benchmark(count),
+            None => eprintln!("Can't run the benchmark, --bench needs a number of cards"),
+        }
+        return;
+    }
+
+    let now = Instant::now();
+
+    let past_end = match args.iter().position(|a| a == "--past-end") {
+        Some(i) => match args.get(i + 1).map(|a| a.as_str()) {
+            Some("clamp") => PastEnd::Clamp,
+            Some("error") => PastEnd::Error,
+            other => {
+                eprintln!(
+                    "Can't handle wins past the end with {}, use clamp or error",
+                    other.unwrap_or("nothing")
+                );
+                return;
+            }
+        },
+        None => PastEnd::Clamp,
+    };
+
+    let scoring = match args.iter().position(|a| a == "--scoring") {
+        Some(i) => match args.get(i + 1).and_then(|a| Scoring::parse(a)) {
+            Some(scoring) => scoring,
+            None => {
+                eprintln!(
+                    "Can't score with {}",
+                    args.get(i + 1).map_or("nothing", |a| a.as_str())
+                );
+                return;
+            }
+        },
+        None => Scoring::Doubling,
+    };
+
+    let input = match Input::from_args(&args) {
+        Ok(input) => input,
+        Err(e) => {
+            eprintln!("Can't pick the input, {}", e);
+            return;
+        }
+    };
+    let lines = match input.lines() {
+        Ok(lines) => lines,
+        Err(e) => {
+            eprintln!("Can't read {}, {}", input, e);
+            return;
+        }
+    };
+
+    let copies = parse_cards(lines.map(|l| l.unwrap()))
+        .and_then(|cards| Ok((count_copies(&cards, past_end)?, cards)));
+
+    let (copies, cards) = match copies {
+        Ok(copies) => copies,
+        Err(e) => {
+            eprintln!("Can't use the cards, {}", e);
+            return;
+        }
+    };
+
+    match total_points(&cards, &scoring) {
+        Ok(score) => println!("Total win sum is {}", score),
+        Err(e) => eprintln!("Can't score the cards, {}", e),
+    }
+
+    if args.iter().any(|a| a == "--copies") {
+        for (card, nrof) in cards.iter().zip(&copies) {
+            println!("Card {} has {} copies", card.id, nrof);
+        }
+    }
+
+    let total = match total_copies(&copies) {
+        Ok(total) => total,
+        Err(e) => {
+            eprintln!("Can't count the cards, {}", e);
+            return;
+        }
+    };
+    println!("Total count is {}", total);
+
+    if args.iter().any(|a| a == "--simulate") {
+        let contributions = match contributions(&cards, &copies) {
+            Ok(contributions) => contributions,
+            Err(e) => {
+                eprintln!("Can't simulate the cards, {}", e);
+                return;
+            }
+        };
+
+        for (card, contribution) in cards.iter().zip(&contributions) {
+            println!(
+                "Card {} contributed {} copies downstream",
+                card.id, contribution
+            );
+        }
+
+        if let Some((card, contribution)) =
+            cards.iter().zip(&contributions).max_by_key(|(_, c)| **c)
+        {
+            match total.checked_sub(*contribution) {
+                Some(without) => println!(
+                    "Card {} matters most, without its wins the total count would be {}",
+                    card.id, without
+                ),
+                None => eprintln!(
+                    "Can't simulate the cards, card {} contributes more than the total",
+                    card.id
+                ),
+            }
+        }
+    }
+
+    println!("Duration: {}", now.elapsed().as_millis());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Cards cut from the middle of a table, so without the check that they start at card 1.
+    fn cards_of(lines: &[&str]) -> Vec<Card> {
+        let card_reg = card_regex();
+
+        lines
+            .iter()
+            .map(|l| parse_card(&card_reg, l).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_get_card_score_should_return_correct_score() {
+        let line = "Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53";
+
+        let card = parse_card(&card_regex(), line).unwrap();
+
+        assert_eq!(card.matches(), 4);
+        assert_eq!(Scoring::Doubling.points(card.matches()), Some(8));
+        assert_eq!(get_card_wins(line), 4);
+    }
+
+    #[test]
+    fn test_get_card_score_with_zero_score_should_return_correctly() {
+        let line = "Card 1: 41 48 83 86 17 | 1 2 3 4 5";
+
+        let card = parse_card(&card_regex(), line).unwrap();
+
+        assert_eq!(card.matches(), 0);
+        assert_eq!(Scoring::Doubling.points(card.matches()), Some(0));
+        assert_eq!(get_card_wins(line), 0);
+    }
+
+    #[test]
+    fn test_parse_card() {
+        let card = parse_card(&card_regex(), "Card  12:  1 99 | 99  0 64").unwrap();
+
+        assert_eq!(card.id, 12);
+        assert_eq!(card.winning, 1 << 1 | 1 << 99);
+        assert_eq!(card.have, 1 << 99 | 1 | 1 << 64);
+    }
+
+    #[test]
+    fn test_bitsets_match_regex_wins() {
+        let lines = generate_lines(50);
+        let cards = parse_cards(lines.clone().into_iter()).unwrap();
+
+        for (line, card) in lines.iter().zip(&cards) {
+            assert_eq!(card.matches(), get_card_wins(line), "{}", line);
+        }
+    }
+
+    #[test]
+    fn test_lines_one_card() {
+        let cards = cards_of(&["Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11    0"]);
+
+        let num: u64 = count_copies(&cards, PastEnd::Clamp).unwrap().iter().sum();
+
+        assert_eq!(num, 1)
+    }
+
+    #[test]
+    fn test_lines_two_cards() {
+        let cards = cards_of(&[
+            "Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36",
+            "Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11",
+        ]);
+
+        let num: u64 = count_copies(&cards, PastEnd::Clamp).unwrap().iter().sum();
+
+        assert_eq!(num, 2)
+    }
+
+    #[test]
+    fn test_lines_three_cards() {
+        let cards = cards_of(&[
+            "Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83",
+            "Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36",
+            "Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11",
+        ]);
+
+        let num: u64 = count_copies(&cards, PastEnd::Clamp).unwrap().iter().sum();
+
+        assert_eq!(num, 4)
+    }
+
+    #[test]
+    fn test_lines_four_cards() {
+        let cards = cards_of(&[
+            "Card 1:  1 21 53 59 44 | 69 82 63 72 16 21 14  1",
+            "Card 2: 41 92 73 84 69 | 59 84 76 51 58  5 54 83",
+            "Card 3: 87 83 26 28 32 | 88 30 70 12 93 22 82 36",
+            "Card 4: 31 18 13 56 72 | 74 77 10 23 35 67 36 11",
+        ]);
+
+        let num: u64 = count_copies(&cards, PastEnd::Clamp).unwrap().iter().sum();
+
+        assert_eq!(num, 8)
+    }
+
+    #[test]
+    fn test_lines_example_cards() {
+        let cards = parse_cards(Input::example(1).lines().unwrap().map(|l| l.unwrap())).unwrap();
+
+        let num: u64 = count_copies(&cards, PastEnd::Clamp).unwrap().iter().sum();
+
+        assert_eq!(num, 30);
+        assert_eq!(total_points(&cards, &Scoring::Doubling), Ok(13));
+    }
+
+    #[test]
+    fn test_part_of_puzzle_input() {
+        // card 198 - 63 68  -> 2
+        // card 199 - /      -> 0
+        // card 200 - 49     -> 1
+        // card 201 - 33     -> 1
+        // card 202 - /      -> 0
+        let cards = cards_of(&[
+            "Card 198: 71 62 73 96 79 63 41 17 56 68 | 95 77 16 70 29 68 66 63 98 80 20 18 31 34 52  5 42 22 49  6 25 38 51 75 50",
+            "Card 199: 70 84 46 98 44 45 16 36 29 99 | 78 21 92 77 32 91 22 90 76 74 42 55 51 69 94 64 26 65 41 97 10 34 15 35  9",
+            "Card 200: 96 60 87 21 80 48 44 69  3 49 |  2 65 66 94 55 62 72 52 86 15 30 71 45 82 49 47 81 33 14 42  4  1 51 75 34",
+            "Card 201: 55 53 33 19  1 70 17 61  2 72 | 62  6 30 86 45 71 46 33 15 90 73 37 18 12 68 87 89 49  8 60 52 22 51 25 74",
+            "Card 202:  5 47 96 53 54 14 77 29 12  3 | 26 71 91 86 59 70 78  8 83 92 35 64  9 79 84 34 36 93 90 40 16 44 51  6  4",
+        ]);
+
+        let num: u64 = count_copies(&cards, PastEnd::Clamp).unwrap().iter().sum();
+
+        assert_eq!(num, 12)
+    }
+
+    #[test]
+    fn test_copies_per_card() {
+        let cards = parse_cards(Input::example(1).lines().unwrap().map(|l| l.unwrap())).unwrap();
+
+        assert_eq!(
+            count_copies(&cards, PastEnd::Clamp).unwrap(),
+            vec![1, 2, 4, 8, 14, 1]
+        );
+    }
+
+    #[test]
+    fn test_millions_of_cards() {
+        // every card wins a copy of the next one, so card n ends up with n copies
+        let nrof: u64 = 2_000_000;
+        let cards: Vec<Card> = (1..=nrof as usize)
+            .map(|id| Card {
+                id,
+                winning: 1 << 7,
+                have: 1 << 7 | 1 << 8,
+            })
+            .collect();
+
+        let copies = count_copies(&cards, PastEnd::Clamp).unwrap();
+
+        assert_eq!(copies[41], 42);
+        assert_eq!(copies.iter().sum::<u64>(), nrof * (nrof + 1) / 2);
+    }
+
+    #[test]
+    fn test_too_many_copies() {
+        // every card wins a copy of the next two, so the copies grow like the Fibonacci numbers
+        // and pass a u64 at card 92
+        let cards: Vec<Card> = (1..=100)
+            .map(|id| Card {
+                id,
+                winning: 1 << 7 | 1 << 8,
+                have: 1 << 7 | 1 << 8,
+            })
+            .collect();
+
+        assert_eq!(
+            count_copies(&cards, PastEnd::Clamp).err(),
+            Some(CardError::TooManyCopies { card: 92 })
+        );
+        assert_eq!(
+            contributions(&cards[..60], &[u64::MAX; 60]).err(),
+            Some(CardError::TooManyCopies { card: 1 })
+        );
+        assert_eq!(total_copies(&[u64::MAX, 1]), Err(CardError::TooManyCards));
+    }
+
+    #[test]
+    fn test_invalid_cards() {
+        let card_reg = card_regex();
+
+        assert_eq!(
+            parse_card(&card_reg, "Card 1: 41 48 | 83 x6").err(),
+            Some(CardError::Malformed(String::from("Card 1: 41 48 | 83 x6")))
+        );
+        assert_eq!(
+            parse_card(&card_reg, "Card 2: 41 48 41 | 83").err(),
+            Some(CardError::DuplicateNumber {
+                card: 2,
+                number: 41
+            })
+        );
+        assert_eq!(
+            parse_card(&card_reg, "Card 3: 41 | 83 7 83").err(),
+            Some(CardError::DuplicateNumber {
+                card: 3,
+                number: 83
+            })
+        );
+        assert_eq!(
+            parse_card(&card_reg, "Card 4: 41 | 128").err(),
+            Some(CardError::NumberOutOfRange {
+                card: 4,
+                number: 128
+            })
+        );
+    }
+
+    #[test]
+    fn test_card_gaps() {
+        let lines = ["Card 1: 1 | 1", "Card 2: 1 | 2", "Card 4: 1 | 2"];
+
+        assert_eq!(
+            parse_cards(lines.iter().map(|l| String::from(*l))).err(),
+            Some(CardError::Gap {
+                previous: 2,
+                card: 4
+            })
+        );
+    }
+
+    #[test]
+    fn test_cards_start_at_one() {
+        let lines = ["Card 2: 1 | 1", "Card 3: 1 | 2"];
+
+        assert_eq!(
+            parse_cards(lines.iter().map(|l| String::from(*l))).err(),
+            Some(CardError::Gap {
+                previous: 0,
+                card: 2
+            })
+        );
+        assert_eq!(
+            CardError::Gap {
+                previous: 0,
+                card: 2
+            }
+            .to_string(),
+            "card 2 comes first instead of card 1"
+        );
+    }
+
+    #[test]
+    fn test_wins_past_end() {
+        let cards = cards_of(&["Card 1: 1 2 | 2 1", "Card 2: 1 | 2", "Card 3: 3 | 3"]);
+
+        assert_eq!(count_copies(&cards, PastEnd::Clamp).unwrap(), vec![1, 2, 2]);
+        assert_eq!(
+            count_copies(&cards, PastEnd::Error).err(),
+            Some(CardError::PastEnd {
+                card: 3,
+                matches: 1
+            })
+        );
+    }
+
+    #[test]
+    fn test_scoring() {
+        let points = |scoring: &Scoring| -> Vec<u64> {
+            (0..7).map(|m| scoring.points(m).unwrap()).collect()
+        };
+
+        assert_eq!(points(&Scoring::Doubling), vec![0, 1, 2, 4, 8, 16, 32]);
+        assert_eq!(points(&Scoring::Linear), vec![0, 1, 2, 3, 4, 5, 6]);
+        assert_eq!(points(&Scoring::Fibonacci), vec![0, 1, 1, 2, 3, 5, 8]);
+        assert_eq!(
+            points(&Scoring::Table(vec![0, 10, 15])),
+            vec![0, 10, 15, 15, 15, 15, 15]
+        );
+        assert_eq!(Scoring::Table(vec![]).points(3), Some(0));
+    }
+
+    #[test]
+    fn test_scoring_overflow() {
+        assert_eq!(Scoring::Doubling.points(64), Some(1 << 63));
+        assert_eq!(Scoring::Doubling.points(65), None);
+        assert_eq!(Scoring::Fibonacci.points(93), Some(12200160415121876738));
+        assert_eq!(Scoring::Fibonacci.points(94), None);
+
+        let numbers: Vec<String> = (0..70).map(|n| n.to_string()).collect();
+        let line = format!("Card 1: {} | {}", numbers.join(" "), numbers.join(" "));
+        let cards = cards_of(&[line.as_str()]);
+
+        assert_eq!(
+            total_points(&cards, &Scoring::Doubling),
+            Err(CardError::TooManyPoints { card: 1 })
+        );
+        assert_eq!(total_points(&cards, &Scoring::Linear), Ok(70));
+
+        let line = format!(
+            "Card 2: {} | {}",
+            numbers[..64].join(" "),
+            numbers[..64].join(" ")
+        );
+        let cards = cards_of(&[line.as_str(), line.as_str()]);
+
+        assert_eq!(
+            total_points(&cards, &Scoring::Doubling),
+            Err(CardError::TooManyPointsInTotal)
+        );
+    }
+
+    #[test]
+    fn test_parse_scoring() {
+        assert_eq!(Scoring::parse("fibonacci"), Some(Scoring::Fibonacci));
+        assert_eq!(
+            Scoring::parse("0, 1,3,6"),
+            Some(Scoring::Table(vec![0, 1, 3, 6]))
+        );
+        assert_eq!(Scoring::parse("quadratic"), None);
+    }
+
+    #[test]
+    fn test_downstream_contributions() {
+        let mut cards =
+            parse_cards(Input::example(1).lines().unwrap().map(|l| l.unwrap())).unwrap();
+        let copies = count_copies(&cards, PastEnd::Clamp).unwrap();
+        let downstream = downstream_per_copy(&cards).unwrap();
+
+        assert_eq!(downstream, vec![14, 6, 3, 1, 0, 0]);
+        assert_eq!(
+            contributions(&cards, &copies),
+            Ok(vec![14, 12, 12, 8, 0, 0])
+        );
+
+        // without a card's wins, the total drops by exactly what the card contributed
+        let total: u64 = copies.iter().sum();
+        for i in 0..cards.len() {
+            let have = cards[i].have;
+            cards[i].have = 0;
+
+            let without: u64 = count_copies(&cards, PastEnd::Clamp).unwrap().iter().sum();
+            assert_eq!(without, total - copies[i] * downstream[i]);
+
+            cards[i].have = have;
+        }
+    }
+}
@@ -1,7 +1,8 @@
-use std::fs;
+use std::env;
 
 use std::cmp;
 
+use aoc::Input;
 use regex::Regex;
 
 struct Map {
@@ -15,20 +16,12 @@ struct Almanac {
     maps: [Vec<Map>; 7],
 }
 
-fn read_lines(file_path: &str) -> Vec<String> {
-    fs::read_to_string(file_path)
-        .unwrap()
-        .lines()
-        .map(String::from)
-        .collect()
-}
-
 fn process_input(lines: Vec<String>) -> Almanac {
     let dec_reg = Regex::new(r"[\d]+").unwrap();
     let map_reg = Regex::new(r"([\d]+) ([\d]+) ([\d]+)").unwrap();
 
     // first line are the seeds
-    let seeds = lines.get(0).unwrap();
+    let seeds = lines.first().unwrap();
 
     let seeds_num: Vec<u64> = dec_reg
         .find_iter(seeds)
@@ -75,7 +68,7 @@ fn get_lowest_location_number(al: Almanac) -> u64 {
 
     for s in al.seeds {
         let mut trace = s;
-        for (_i, vec_map) in al.maps.iter().enumerate() {
+        for vec_map in al.maps.iter() {
             for m in vec_map {
                 if trace >= m.source && trace < m.source + m.range {
                     // this seed/trace falls within the range,
@@ -97,7 +90,22 @@ fn get_lowest_location_number(al: Almanac) -> u64 {
 }
 
 fn main() {
-    let al = process_input(read_lines("./puzzle.input"));
+    let args: Vec<String> = env::args().collect();
+    let input = match Input::from_args(&args) {
+        Ok(input) => input,
+        Err(e) => {
+            eprintln!("Can't pick the input, {}", e);
+            return;
+        }
+    };
+    let lines = match input.read_lines() {
+        Ok(lines) => lines,
+        Err(e) => {
+            eprintln!("Can't read {}, {}", input, e);
+            return;
+        }
+    };
+    let al = process_input(lines);
     let lowest_loc = get_lowest_location_number(al);
 
     println!("Lowest location number is {}", lowest_loc);
@@ -109,7 +117,7 @@ mod tests {
 
     #[test]
     fn test_process_input() {
-        let al = process_input(read_lines("./example.input"));
+        let al = process_input(Input::example(1).read_lines().unwrap());
         let lowest_loc = get_lowest_location_number(al);
 
         assert_eq!(lowest_loc, 35);
@@ -1,7 +1,8 @@
-use std::fs;
+use std::env;
 
 use std::cmp;
 
+use aoc::Input;
 use regex::Regex;
 
 #[derive(Debug)]
@@ -22,20 +23,12 @@ struct Almanac {
     maps: [Vec<Map>; 7],
 }
 
-fn read_lines(file_path: &str) -> Vec<String> {
-    fs::read_to_string(file_path)
-        .unwrap()
-        .lines()
-        .map(String::from)
-        .collect()
-}
-
 fn process_input(lines: Vec<String>) -> Almanac {
     let dec_reg = Regex::new(r"[\d]+ [\d]+").unwrap();
     let map_reg = Regex::new(r"([\d]+) ([\d]+) ([\d]+)").unwrap();
 
     // first line are the seeds
-    let seeds_str = lines.get(0).unwrap();
+    let seeds_str = lines.first().unwrap();
 
     let seeds_num: Vec<&str> = dec_reg.find_iter(seeds_str).map(|f| f.as_str()).collect();
 
@@ -224,7 +217,22 @@ fn get_lowest_location_number(mut al: Almanac) -> u64 {
 }
 
 fn main() {
-    let al = process_input(read_lines("./example.input"));
+    let args: Vec<String> = env::args().collect();
+    let input = match Input::from_args(&args) {
+        Ok(input) => input,
+        Err(e) => {
+            eprintln!("Can't pick the input, {}", e);
+            return;
+        }
+    };
+    let lines = match input.read_lines() {
+        Ok(lines) => lines,
+        Err(e) => {
+            eprintln!("Can't read {}, {}", input, e);
+            return;
+        }
+    };
+    let al = process_input(lines);
     let lowest_loc = get_lowest_location_number(al);
 
     println!("Lowest location number is {}", lowest_loc);
@@ -236,7 +244,7 @@ mod tests {
 
     #[test]
     fn test_process_input() {
-        let al = process_input(read_lines("./example.input"));
+        let al = process_input(Input::example(1).read_lines().unwrap());
         let lowest_loc = get_lowest_location_number(al);
 
         assert_eq!(lowest_loc, 46);
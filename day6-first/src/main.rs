@@ -1,15 +1,8 @@
-use std::fs;
+use std::env;
 
+use aoc::Input;
 use regex::Regex;
 
-fn read_lines(file_path: &str) -> Vec<String> {
-    fs::read_to_string(file_path)
-        .unwrap()
-        .lines()
-        .map(String::from)
-        .collect()
-}
-
 fn process_input(input: Vec<String>) -> Vec<(u16, u16)> {
     let dec_reg = Regex::new(r"[\d]+").unwrap();
 
@@ -51,7 +44,22 @@ fn process_races(races: Vec<(u16, u16)>) -> u32 {
 }
 
 fn main() {
-    let races = process_input(read_lines("./puzzle.input"));
+    let args: Vec<String> = env::args().collect();
+    let input = match Input::from_args(&args) {
+        Ok(input) => input,
+        Err(e) => {
+            eprintln!("Can't pick the input, {}", e);
+            return;
+        }
+    };
+    let lines = match input.read_lines() {
+        Ok(lines) => lines,
+        Err(e) => {
+            eprintln!("Can't read {}, {}", input, e);
+            return;
+        }
+    };
+    let races = process_input(lines);
     let num = process_races(races);
 
     println!("Race multiplication number {}", num);
@@ -0,0 +1,584 @@
+use std::{
+    collections::{HashMap, HashSet},
+    env,
+    fmt::{self, Write},
+};
+
+use aoc::Input;
+use regex::Regex;
+
+struct Ghost {
+    cur_dir: String,
+}
+
+struct Movements {
+    directions: String,
+    ghosts: Vec<Ghost>,
+    map: HashMap<String, Node>,
+}
+
+#[derive(Debug)]
+struct Node {
+    left: String,
+    right: String,
+}
+
+/// Decides which nodes ghosts start from or have to end up in.
+enum NodeMatcher {
+    Exact(String),
+    Suffix(String),
+    Pattern(Regex),
+}
+
+impl NodeMatcher {
+    fn matches(&self, key: &str) -> bool {
+        match self {
+            NodeMatcher::Exact(name) => key == name,
+            NodeMatcher::Suffix(suffix) => key.ends_with(suffix.as_str()),
+            NodeMatcher::Pattern(reg) => reg.is_match(key),
+        }
+    }
+}
+
+const CYCLE_COLOURS: [&str; 6] = ["red", "blue", "darkgreen", "orange", "purple", "brown"];
+
+fn lcm(a: u64, b: u64) -> u64 {
+    (a * b) / gcd(a, b)
+}
+
+fn gcd(mut a: u64, mut b: u64) -> u64 {
+    while b != 0 {
+        let t = b;
+        b = a % b;
+        a = t;
+    }
+
+    a
+}
+
+/// Why a matcher given on the command line can't be used.
+#[derive(Debug, PartialEq)]
+enum MatcherError {
+    /// Nothing follows the flag that should be followed by a spec.
+    Missing(String),
+    /// The spec isn't one of `exact:<name>`, `suffix:<suffix>` or `regex:<pattern>`.
+    Unknown(String),
+    /// The pattern after `regex:` doesn't compile.
+    BadPattern(String),
+}
+
+impl fmt::Display for MatcherError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MatcherError::Missing(flag) => write!(f, "there's no spec after {}", flag),
+            MatcherError::Unknown(spec) => write!(f, "{} isn't a node matcher", spec),
+            MatcherError::BadPattern(pattern) => write!(f, "{} isn't a valid regex", pattern),
+        }
+    }
+}
+
+/// Why the steps until every ghost is at an end node can't be counted.
+#[derive(Debug, PartialEq)]
+enum StepsError {
+    /// No node matches the start, so there are no ghosts to walk.
+    NoStart,
+    /// The ghost starting at this node loops forever without passing an end node.
+    NeverEnds(String),
+    /// The ghost starting at this node needs `first` steps to reach an end node, but then
+    /// loops back to one in `period` steps, so the least common multiple isn't the answer.
+    UnstableLoop {
+        start: String,
+        first: u64,
+        period: u64,
+    },
+}
+
+impl fmt::Display for StepsError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            StepsError::NoStart => write!(f, "no node matches the start"),
+            StepsError::NeverEnds(start) => {
+                write!(f, "the ghost from {} never reaches an end node", start)
+            }
+            StepsError::UnstableLoop {
+                start,
+                first,
+                period,
+            } => write!(
+                f,
+                "the ghost from {} ends after {} steps but loops every {}",
+                start, first, period
+            ),
+        }
+    }
+}
+
+/// Parses a matcher given as `exact:<name>`, `suffix:<suffix>` or `regex:<pattern>`.
+fn parse_matcher(spec: &str) -> Result<NodeMatcher, MatcherError> {
+    match spec.split_once(':') {
+        Some(("exact", name)) => Ok(NodeMatcher::Exact(name.to_string())),
+        Some(("suffix", suffix)) => Ok(NodeMatcher::Suffix(suffix.to_string())),
+        Some(("regex", pattern)) => Regex::new(pattern)
+            .map(NodeMatcher::Pattern)
+            .map_err(|_| MatcherError::BadPattern(pattern.to_string())),
+        _ => Err(MatcherError::Unknown(spec.to_string())),
+    }
+}
+
+fn parse_input(lines: &[String], start: &NodeMatcher) -> Movements {
+    let node_reg = Regex::new(r"([A-Za-z0-9]+) = \(([A-Za-z0-9]+), ([A-Za-z0-9]+)\)").unwrap();
+
+    let directions = lines.first().unwrap().as_str().to_string();
+
+    let mut movements: Movements = Movements {
+        ghosts: vec![],
+        directions,
+        map: HashMap::new(),
+    };
+
+    for line in lines.iter().skip(2) {
+        let caps = node_reg.captures(line.as_str()).unwrap();
+
+        let hash = caps.get(1).unwrap().as_str().to_string();
+
+        if start.matches(&hash) {
+            movements.ghosts.push(Ghost {
+                cur_dir: hash.clone(),
+            })
+        }
+
+        movements.map.entry(hash).or_insert(Node {
+            left: caps.get(2).unwrap().as_str().to_string(),
+            right: caps.get(3).unwrap().as_str().to_string(),
+        });
+    }
+
+    movements
+}
+
+/// Walks from `from`, starting at the `offset`-th direction, until an end node is reached
+/// after at least one step. Returns the steps taken and the end node, or `None` when the walk
+/// has been through every (node, direction) state without reaching one.
+fn steps_to_end(
+    movements: &Movements,
+    from: &str,
+    offset: u64,
+    end: &NodeMatcher,
+) -> Option<(u64, String)> {
+    let dirs: Vec<char> = movements.directions.chars().collect();
+    let max_steps = (movements.map.len() * dirs.len()) as u64;
+    let mut key = from;
+    let mut steps: u64 = 0;
+
+    while steps == 0 || !end.matches(key) {
+        if steps > max_steps {
+            return None;
+        }
+
+        let node: &Node = &movements.map[key];
+        match dirs[((offset + steps) % dirs.len() as u64) as usize] {
+            'R' => key = &node.right,
+            'L' => key = &node.left,
+            _ => panic!("Unexpected direction entry"),
+        }
+
+        steps += 1;
+    }
+
+    Some((steps, key.to_string()))
+}
+
+fn calculate_steps(movements: &Movements, end: &NodeMatcher) -> Result<u64, StepsError> {
+    if movements.ghosts.is_empty() {
+        return Err(StepsError::NoStart);
+    }
+
+    let mut lcm_number: u64 = 1;
+
+    for ghost in &movements.ghosts {
+        let (first, node) = steps_to_end(movements, &ghost.cur_dir, 0, end)
+            .ok_or_else(|| StepsError::NeverEnds(ghost.cur_dir.clone()))?;
+
+        // a single ghost is done the first time it gets there, but with more of them we
+        // need to know the ghost loops back to its end node in that same nrof steps,
+        // because only then the least common multiple of all these steps is the exact
+        // step on which all ghosts end up at their destination
+        if movements.ghosts.len() > 1 {
+            let (period, _) = steps_to_end(movements, &node, first, end)
+                .ok_or_else(|| StepsError::NeverEnds(ghost.cur_dir.clone()))?;
+
+            if period != first {
+                return Err(StepsError::UnstableLoop {
+                    start: ghost.cur_dir.clone(),
+                    first,
+                    period,
+                });
+            }
+        }
+
+        lcm_number = lcm(lcm_number, first);
+    }
+
+    Ok(lcm_number)
+}
+
+/// Walks a ghost from `start` until it revisits a (node, direction index) state, and returns
+/// the (node, direction) pairs that make up the loop it ends up in.
+fn find_cycle(start: &str, directions: &str, map: &HashMap<String, Node>) -> Vec<(String, char)> {
+    let dirs: Vec<char> = directions.chars().collect();
+    let mut seen: HashMap<(String, usize), usize> = HashMap::new();
+    let mut path: Vec<(String, char)> = Vec::new();
+    let mut key = start.to_string();
+    let mut i = 0;
+
+    while !seen.contains_key(&(key.clone(), i)) {
+        seen.insert((key.clone(), i), path.len());
+        path.push((key.clone(), dirs[i]));
+
+        let node = &map[&key];
+        key = match dirs[i] {
+            'R' => node.right.clone(),
+            'L' => node.left.clone(),
+            _ => panic!("Unexpected direction entry"),
+        };
+        i = (i + 1) % dirs.len();
+    }
+
+    path.split_off(seen[&(key, i)])
+}
+
+fn to_dot(movements: &Movements, end: &NodeMatcher, with_cycles: bool) -> String {
+    let mut keys: Vec<&String> = movements.map.keys().collect();
+    keys.sort();
+
+    // map every node and taken edge in a ghost's cycle to that ghost's colour
+    let mut node_colours: HashMap<String, &str> = HashMap::new();
+    let mut edge_colours: HashMap<(String, char), &str> = HashMap::new();
+    if with_cycles {
+        for (i, ghost) in movements.ghosts.iter().enumerate() {
+            let colour = CYCLE_COLOURS[i % CYCLE_COLOURS.len()];
+            for (key, dir) in find_cycle(&ghost.cur_dir, &movements.directions, &movements.map) {
+                node_colours.entry(key.clone()).or_insert(colour);
+                edge_colours.entry((key, dir)).or_insert(colour);
+            }
+        }
+    }
+
+    let mut dot = String::from("digraph network {\n");
+
+    for key in &keys {
+        let mut attrs: Vec<String> = Vec::new();
+        if movements.ghosts.iter().any(|g| &g.cur_dir == *key) {
+            attrs.push("style=filled, fillcolor=palegreen".to_string());
+        } else if end.matches(key) {
+            attrs.push("style=filled, fillcolor=lightcoral".to_string());
+        }
+        if let Some(colour) = node_colours.get(*key) {
+            attrs.push(format!("color={}, penwidth=2", colour));
+        }
+
+        if !attrs.is_empty() {
+            writeln!(dot, "    \"{}\" [{}];", key, attrs.join(", ")).unwrap();
+        }
+    }
+
+    for key in &keys {
+        let node = &movements.map[*key];
+        let mut edges: Vec<(&String, &str, HashSet<&str>)> = Vec::new();
+
+        for (dir, target) in [('L', &node.left), ('R', &node.right)] {
+            let colour = edge_colours.get(&(key.to_string(), dir)).copied();
+            match edges.iter_mut().find(|(t, _, _)| *t == target) {
+                // both directions lead to the same node, so merge them into a single edge
+                Some(edge) => {
+                    edge.1 = "L/R";
+                    edge.2.extend(colour);
+                }
+                None => edges.push((
+                    target,
+                    if dir == 'L' { "L" } else { "R" },
+                    colour.into_iter().collect(),
+                )),
+            }
+        }
+
+        for (target, label, colours) in edges {
+            let mut colours: Vec<&str> = colours.into_iter().collect();
+            colours.sort();
+            match colours.first() {
+                Some(colour) => writeln!(
+                    dot,
+                    "    \"{}\" -> \"{}\" [label=\"{}\", color={}, penwidth=2];",
+                    key, target, label, colour
+                ),
+                None => writeln!(
+                    dot,
+                    "    \"{}\" -> \"{}\" [label=\"{}\"];",
+                    key, target, label
+                ),
+            }
+            .unwrap();
+        }
+    }
+
+    dot.push_str("}\n");
+
+    dot
+}
+
+fn usage() {
+    eprintln!(
+        "Usage: day8 [--start spec] [--end spec] [--dot [--cycles]], \
+        where a spec is exact:<name>, suffix:<suffix> or regex:<pattern>"
+    );
+}
+
+/// The matcher given after `flag`, if the flag is there at all.
+fn matcher_arg(args: &[String], flag: &str) -> Result<Option<NodeMatcher>, MatcherError> {
+    match args.iter().position(|a| a == flag) {
+        Some(i) => match args.get(i + 1) {
+            Some(spec) => parse_matcher(spec).map(Some),
+            None => Err(MatcherError::Missing(flag.to_string())),
+        },
+        None => Ok(None),
+    }
+}
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    let (start, end) = match (matcher_arg(&args, "--start"), matcher_arg(&args, "--end")) {
+        (Ok(start), Ok(end)) => (start, end),
+        (Err(e), _) | (_, Err(e)) => {
+            eprintln!("Can't use the matcher, {}", e);
+            usage();
+            return;
+        }
+    };
+
+    let input = match Input::from_args(&args) {
+        Ok(input) => input,
+        Err(e) => {
+            eprintln!("Can't pick the input, {}", e);
+            return;
+        }
+    };
+    let lines = match input.read_lines() {
+        Ok(lines) => lines,
+        Err(e) => {
+            eprintln!("Can't read {}, {}", input, e);
+            return;
+        }
+    };
+
+    // without matchers both parts are answered, the first one walking from AAA to ZZZ and
+    // the second one walking all ghosts from the nodes ending in A to the ones ending in Z
+    let custom = start.is_some() || end.is_some();
+    let start = start.unwrap_or(NodeMatcher::Suffix(String::from("A")));
+    let end = end.unwrap_or(NodeMatcher::Suffix(String::from("Z")));
+
+    let movements = parse_input(&lines, &start);
+
+    if args.iter().any(|a| a == "--dot") {
+        print!(
+            "{}",
+            to_dot(&movements, &end, args.iter().any(|a| a == "--cycles"))
+        );
+        return;
+    }
+
+    if !custom {
+        let movements = parse_input(&lines, &NodeMatcher::Exact(String::from("AAA")));
+
+        match calculate_steps(&movements, &NodeMatcher::Exact(String::from("ZZZ"))) {
+            Ok(steps) => println!("Number of steps {}", steps),
+            Err(e) => eprintln!("Can't count the steps from AAA, {}", e),
+        }
+    }
+
+    match calculate_steps(&movements, &end) {
+        Ok(steps) if custom => println!("Number of steps {}", steps),
+        Ok(steps) => println!("Number of ghost steps {}", steps),
+        Err(e) => eprintln!("Can't count the ghost steps, {}", e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_cycle() {
+        let movements = parse_input(
+            &Input::example(3).read_lines().unwrap(),
+            &NodeMatcher::Suffix(String::from("A")),
+        );
+
+        let cycle = find_cycle("11A", &movements.directions, &movements.map);
+
+        assert_eq!(
+            cycle,
+            vec![(String::from("11B"), 'R'), (String::from("11Z"), 'L')]
+        );
+    }
+
+    #[test]
+    fn test_dot_output() {
+        let movements = parse_input(
+            &Input::example(3).read_lines().unwrap(),
+            &NodeMatcher::Suffix(String::from("A")),
+        );
+
+        let dot = to_dot(&movements, &NodeMatcher::Suffix(String::from("Z")), false);
+
+        assert!(dot.starts_with("digraph network {\n"));
+        assert!(dot.contains("    \"11A\" [style=filled, fillcolor=palegreen];\n"));
+        assert!(dot.contains("    \"22Z\" [style=filled, fillcolor=lightcoral];\n"));
+        assert!(dot.contains("    \"11A\" -> \"11B\" [label=\"L\"];\n"));
+        assert!(dot.contains("    \"11A\" -> \"XXX\" [label=\"R\"];\n"));
+        assert!(dot.contains("    \"22B\" -> \"22C\" [label=\"L/R\"];\n"));
+        assert!(dot.ends_with("}\n"));
+    }
+
+    #[test]
+    fn test_dot_output_with_cycles() {
+        let movements = parse_input(
+            &Input::example(3).read_lines().unwrap(),
+            &NodeMatcher::Suffix(String::from("A")),
+        );
+
+        let dot = to_dot(&movements, &NodeMatcher::Suffix(String::from("Z")), true);
+
+        assert!(dot.contains("    \"11B\" [color=red, penwidth=2];\n"));
+        assert!(dot.contains("    \"11B\" -> \"11Z\" [label=\"R\", color=red, penwidth=2];\n"));
+        assert!(dot.contains(
+            "    \"22Z\" [style=filled, fillcolor=lightcoral, color=blue, penwidth=2];\n"
+        ));
+        assert!(dot.contains("    \"11A\" -> \"11B\" [label=\"L\"];\n"));
+    }
+
+    #[test]
+    fn test_matchers() {
+        let exact = parse_matcher("exact:AAA").unwrap();
+        let suffix = parse_matcher("suffix:Z").unwrap();
+        let pattern = parse_matcher("regex:^1+[A-Z]$").unwrap();
+
+        assert!(exact.matches("AAA"));
+        assert!(!exact.matches("BAAA"));
+        assert!(suffix.matches("11Z"));
+        assert!(!suffix.matches("Z11"));
+        assert!(pattern.matches("111B"));
+        assert!(!pattern.matches("21B"));
+
+        assert_eq!(
+            parse_matcher("prefix:A").err(),
+            Some(MatcherError::Unknown(String::from("prefix:A")))
+        );
+        assert_eq!(
+            parse_matcher("regex:(A").err(),
+            Some(MatcherError::BadPattern(String::from("(A")))
+        );
+    }
+
+    #[test]
+    fn test_ghost_steps() {
+        let end = NodeMatcher::Suffix(String::from("Z"));
+        let movements = parse_input(
+            &Input::example(3).read_lines().unwrap(),
+            &NodeMatcher::Suffix(String::from("A")),
+        );
+
+        assert_eq!(calculate_steps(&movements, &end), Ok(6));
+    }
+
+    #[test]
+    fn test_single_ghost_steps() {
+        let lines: Vec<String> = vec![
+            "LLR",
+            "",
+            "AAA = (BBB, BBB)",
+            "BBB = (AAA, ZZZ)",
+            "ZZZ = (ZZZ, ZZZ)",
+        ]
+        .into_iter()
+        .map(String::from)
+        .collect();
+        let movements = parse_input(&lines, &NodeMatcher::Exact(String::from("AAA")));
+
+        assert_eq!(
+            calculate_steps(&movements, &NodeMatcher::Exact(String::from("ZZZ"))),
+            Ok(6)
+        );
+    }
+
+    #[test]
+    fn test_long_labels() {
+        let lines: Vec<String> = vec![
+            "RL",
+            "",
+            "START = (Z, middle1)",
+            "middle1 = (FINISH, Z)",
+            "FINISH = (FINISH, FINISH)",
+            "Z = (Z, Z)",
+        ]
+        .into_iter()
+        .map(String::from)
+        .collect();
+        let movements = parse_input(&lines, &parse_matcher("regex:^START$").unwrap());
+
+        assert_eq!(movements.map["middle1"].left, "FINISH");
+        assert_eq!(
+            calculate_steps(&movements, &parse_matcher("exact:FINISH").unwrap()),
+            Ok(2)
+        );
+    }
+
+    #[test]
+    fn test_first_part_steps() {
+        let start = NodeMatcher::Exact(String::from("AAA"));
+        let end = NodeMatcher::Exact(String::from("ZZZ"));
+
+        for (n, expected) in [(1, 2), (2, 6)] {
+            let movements = parse_input(&Input::example(n).read_lines().unwrap(), &start);
+
+            assert_eq!(calculate_steps(&movements, &end), Ok(expected));
+        }
+    }
+
+    #[test]
+    fn test_steps_errors() {
+        let lines: Vec<String> = vec![
+            "L",
+            "",
+            "A1A = (A1Z, A1Z)",
+            "A1Z = (A1Z, A1Z)",
+            "A2A = (XXX, XXX)",
+            "XXX = (A2Z, A2Z)",
+            "A2Z = (A2Z, A2Z)",
+            "B1A = (B1A, B1A)",
+        ]
+        .into_iter()
+        .map(String::from)
+        .collect();
+        let end = NodeMatcher::Suffix(String::from("Z"));
+
+        let movements = parse_input(&lines, &parse_matcher("regex:^A").unwrap());
+        assert_eq!(
+            calculate_steps(&movements, &end),
+            Err(StepsError::UnstableLoop {
+                start: String::from("A2A"),
+                first: 2,
+                period: 1
+            })
+        );
+
+        let movements = parse_input(&lines, &parse_matcher("exact:B1A").unwrap());
+        assert_eq!(
+            calculate_steps(&movements, &end),
+            Err(StepsError::NeverEnds(String::from("B1A")))
+        );
+
+        let movements = parse_input(&lines, &parse_matcher("exact:C1A").unwrap());
+        assert_eq!(calculate_steps(&movements, &end), Err(StepsError::NoStart));
+    }
+}
@@ -0,0 +1,555 @@
+use std::{env, fmt};
+
+use aoc::Input;
+
+fn gcd(mut a: i128, mut b: i128) -> i128 {
+    while b != 0 {
+        let t = b;
+        b = a % b;
+        a = t;
+    }
+
+    a.abs()
+}
+
+/// An exact fraction, always kept in lowest terms with a positive denominator.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Rational {
+    num: i128,
+    den: i128,
+}
+
+impl Rational {
+    fn new(num: i128, den: i128) -> Result<Rational, HistoryError> {
+        if den == 0 {
+            panic!("Rational with a zero denominator");
+        }
+        // the lowest terms can't be taken of these, as their absolute value doesn't fit
+        if num == i128::MIN || den == i128::MIN {
+            return Err(HistoryError::Overflow);
+        }
+
+        let div = gcd(num, den) * den.signum();
+
+        Ok(Rational {
+            num: num / div,
+            den: den / div,
+        })
+    }
+
+    fn from_int(num: i128) -> Rational {
+        Rational { num, den: 1 }
+    }
+
+    /// Adds over the least common denominator, so only the sum itself has to fit.
+    fn add(self, other: Rational) -> Result<Rational, HistoryError> {
+        let g = gcd(self.den, other.den);
+        let num = self
+            .num
+            .checked_mul(other.den / g)
+            .zip(other.num.checked_mul(self.den / g))
+            .and_then(|(a, b)| a.checked_add(b));
+        let den = self.den.checked_mul(other.den / g);
+
+        match (num, den) {
+            (Some(num), Some(den)) => Rational::new(num, den),
+            _ => Err(HistoryError::Overflow),
+        }
+    }
+
+    /// Cancels crosswise before multiplying, so only the product itself has to fit.
+    fn mul(self, other: Rational) -> Result<Rational, HistoryError> {
+        let g1 = gcd(self.num, other.den);
+        let g2 = gcd(other.num, self.den);
+
+        match (
+            (self.num / g1).checked_mul(other.num / g2),
+            (self.den / g2).checked_mul(other.den / g1),
+        ) {
+            (Some(num), Some(den)) => Rational::new(num, den),
+            _ => Err(HistoryError::Overflow),
+        }
+    }
+}
+
+impl fmt::Display for Rational {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.den == 1 {
+            write!(f, "{}", self.num)
+        } else {
+            write!(f, "{}/{}", self.num, self.den)
+        }
+    }
+}
+
+/// A polynomial in `n` with its coefficients ordered from the constant term upwards.
+#[derive(Debug, PartialEq)]
+struct Polynomial {
+    coeffs: Vec<Rational>,
+}
+
+impl Polynomial {
+    fn eval(&self, n: i64) -> Result<Rational, HistoryError> {
+        let n = Rational::from_int(i128::from(n));
+
+        self.coeffs
+            .iter()
+            .rev()
+            .try_fold(Rational::from_int(0), |acc, &coeff| acc.mul(n)?.add(coeff))
+    }
+}
+
+impl fmt::Display for Polynomial {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut first = true;
+
+        for (power, coeff) in self.coeffs.iter().enumerate().rev() {
+            if coeff.num == 0 {
+                continue;
+            }
+
+            let abs = Rational {
+                num: coeff.num.abs(),
+                den: coeff.den,
+            };
+            match (first, coeff.num < 0) {
+                (true, true) => write!(f, "-")?,
+                (false, true) => write!(f, " - ")?,
+                (false, false) => write!(f, " + ")?,
+                (true, false) => (),
+            }
+            first = false;
+
+            if abs != Rational::from_int(1) || power == 0 {
+                write!(f, "{}", abs)?;
+            }
+            match power {
+                0 => (),
+                1 => write!(f, "n")?,
+                _ => write!(f, "n^{}", power)?,
+            }
+        }
+
+        if first {
+            write!(f, "0")?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum HistoryError {
+    /// A history needs at least two values to have any differences.
+    TooShort,
+    /// The differences or extrapolated values grew past what fits in an `i128`.
+    Overflow,
+}
+
+impl fmt::Display for HistoryError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            HistoryError::TooShort => write!(f, "fewer than two values"),
+            HistoryError::Overflow => write!(f, "values overflowed an i128"),
+        }
+    }
+}
+
+/// A history stored as the first column of its difference table, which are the coefficients
+/// of the polynomial generating it in Newton forward difference form.
+struct History {
+    coeffs: Vec<i128>,
+}
+
+impl History {
+    fn new(nums: &[i64]) -> Result<History, HistoryError> {
+        if nums.len() < 2 {
+            return Err(HistoryError::TooShort);
+        }
+
+        let mut coeffs: Vec<i128> = nums.iter().map(|&n| i128::from(n)).collect();
+
+        // replace the values by their differences in place, one row of the table at a time,
+        // leaving the first value of every row behind
+        for row in 1..coeffs.len() {
+            for i in (row..coeffs.len()).rev() {
+                coeffs[i] = coeffs[i]
+                    .checked_sub(coeffs[i - 1])
+                    .ok_or(HistoryError::Overflow)?;
+            }
+        }
+
+        Ok(History { coeffs })
+    }
+
+    fn len(&self) -> i64 {
+        self.coeffs.len() as i64
+    }
+
+    /// Whether the differences settle on a constant row before the last one. The last row
+    /// holds a single difference, which is trivially constant, so a history that only
+    /// settles there is still extrapolated from it, but nothing shows a polynomial of lower
+    /// degree than the history is long generates it.
+    fn is_confirmed(&self) -> bool {
+        self.degree() + 1 < self.coeffs.len()
+    }
+
+    /// Degree of the polynomial generating the history, i.e. the row of the difference table
+    /// after which all differences are zero.
+    fn degree(&self) -> usize {
+        self.coeffs.iter().rposition(|&c| c != 0).unwrap_or(0)
+    }
+
+    /// Value of the history at index `n`, where the known values sit at `0..len` and `n` may
+    /// lie on either side of them.
+    fn value_at(&self, n: i64) -> Result<i128, HistoryError> {
+        let n = i128::from(n);
+        let mut value: i128 = 0;
+        // binomial coefficient n over k, which also works for negative n
+        let mut binom: i128 = 1;
+
+        for (k, &coeff) in self.coeffs.iter().enumerate().take(self.degree() + 1) {
+            value = coeff
+                .checked_mul(binom)
+                .and_then(|term| value.checked_add(term))
+                .ok_or(HistoryError::Overflow)?;
+
+            let k = k as i128;
+            binom = binom.checked_mul(n - k).ok_or(HistoryError::Overflow)? / (k + 1);
+        }
+
+        Ok(value)
+    }
+
+    /// The polynomial `p(n)` generating the history, with the first known value at `p(0)`.
+    /// Its exact coefficients can overflow even when the values of the history don't.
+    fn polynomial(&self) -> Result<Polynomial, HistoryError> {
+        let mut coeffs = vec![Rational::from_int(0); self.degree() + 1];
+        // falling factorial n * (n - 1) * ... * (n - k + 1) divided by k!, i.e. n over k
+        let mut binom = vec![Rational::from_int(1)];
+
+        for (k, &coeff) in self.coeffs.iter().enumerate().take(self.degree() + 1) {
+            for (power, &b) in binom.iter().enumerate() {
+                coeffs[power] = coeffs[power].add(b.mul(Rational::from_int(coeff))?)?;
+            }
+
+            // multiply by (n - k) / (k + 1) to get n over k + 1
+            let factor = Rational::new(1, k as i128 + 1)?;
+            let mut next = vec![Rational::from_int(0); binom.len() + 1];
+            for (power, &b) in binom.iter().enumerate() {
+                let term = b.mul(factor)?;
+                next[power + 1] = next[power + 1].add(term)?;
+                next[power] = next[power].add(term.mul(Rational::from_int(-(k as i128)))?)?;
+            }
+            binom = next;
+        }
+
+        Ok(Polynomial { coeffs })
+    }
+
+    /// The next `steps` values after the end of the history.
+    fn extrapolate_forward(&self, steps: usize) -> Result<Vec<i128>, HistoryError> {
+        (0..steps as i64)
+            .map(|s| self.value_at(self.len() + s))
+            .collect()
+    }
+
+    /// The `steps` values before the start of the history, nearest first.
+    fn extrapolate_backward(&self, steps: usize) -> Result<Vec<i128>, HistoryError> {
+        (1..=steps as i64).map(|s| self.value_at(-s)).collect()
+    }
+}
+
+/// The values right before and after a history, both read from the same difference table.
+fn extrapolate(nums: &[i64]) -> Result<(i128, i128), HistoryError> {
+    let history = History::new(nums)?;
+
+    Ok((history.value_at(-1)?, history.value_at(history.len())?))
+}
+
+/// The `steps` values before and after a history, both read from the same difference table.
+fn extrapolate_steps(nums: &[i64], steps: usize) -> Result<(Vec<i128>, Vec<i128>), HistoryError> {
+    let history = History::new(nums)?;
+
+    Ok((
+        history.extrapolate_backward(steps)?,
+        history.extrapolate_forward(steps)?,
+    ))
+}
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let show_polynomials = args.iter().any(|a| a == "--poly");
+    let eval_at: Option<i64> = match args.iter().position(|a| a == "--at") {
+        Some(i) => match args.get(i + 1).and_then(|n| n.parse().ok()) {
+            Some(n) => Some(n),
+            None => {
+                eprintln!("Can't evaluate the polynomials, --at needs a number");
+                return;
+            }
+        },
+        None => None,
+    };
+    // the totals are of the values this many steps before and after every history
+    let steps: usize = match args.iter().position(|a| a == "--steps") {
+        Some(i) => match args.get(i + 1).and_then(|s| s.parse().ok()) {
+            Some(steps) if steps > 0 => steps,
+            _ => {
+                eprintln!("Can't extrapolate, --steps needs a number of steps above 0");
+                return;
+            }
+        },
+        None => 1,
+    };
+
+    let input = match Input::from_args(&args) {
+        Ok(input) => input,
+        Err(e) => {
+            eprintln!("Can't pick the input, {}", e);
+            return;
+        }
+    };
+    let lines = match input.lines() {
+        Ok(lines) => lines,
+        Err(e) => {
+            eprintln!("Can't read {}, {}", input, e);
+            return;
+        }
+    };
+
+    let mut prev_sum: i128 = 0;
+    let mut next_sum: i128 = 0;
+
+    for (i, line) in lines.enumerate() {
+        let nums: Vec<i64> = line
+            .unwrap()
+            .split(' ')
+            .map(|s| s.parse().unwrap())
+            .collect();
+
+        let history = History::new(&nums);
+
+        if let Ok(history) = &history {
+            if !history.is_confirmed() {
+                eprintln!(
+                    "Line {} reached no constant difference row before the last one",
+                    i + 1
+                );
+            }
+        }
+
+        if show_polynomials || eval_at.is_some() {
+            match history.and_then(|history| history.polynomial()) {
+                Ok(polynomial) => {
+                    if show_polynomials {
+                        println!("p(n) = {}", polynomial);
+                    }
+                    if let Some(n) = eval_at {
+                        match polynomial.eval(n) {
+                            Ok(value) => println!("p({}) = {}", n, value),
+                            Err(err) => eprintln!("Can't evaluate line {}: {}", i + 1, err),
+                        }
+                    }
+                }
+                Err(err) => eprintln!("No polynomial for line {}: {}", i + 1, err),
+            }
+        }
+
+        let values = match steps {
+            1 => extrapolate(&nums),
+            _ => extrapolate_steps(&nums, steps)
+                .map(|(prev, next)| (prev[steps - 1], next[steps - 1])),
+        };
+
+        match values {
+            Ok((prev, next)) => {
+                prev_sum += prev;
+                next_sum += next;
+            }
+            Err(err) => eprintln!("Skipping line {}: {}", i + 1, err),
+        }
+    }
+
+    if steps == 1 {
+        println!("Total sum of next values is {}", next_sum);
+        println!("Total sum of previous values is {}", prev_sum);
+    } else {
+        println!("Total sum of values {} steps ahead is {}", steps, next_sum);
+        println!("Total sum of values {} steps back is {}", steps, prev_sum);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_correct_prediction() {
+        let seq1 = vec![0, 3, 6, 9, 12, 15];
+        let seq2 = vec![1, 3, 6, 10, 15, 21];
+        let seq3 = vec![10, 13, 16, 21, 30, 45];
+
+        let (prev1, next1) = extrapolate(&seq1).unwrap();
+        let (prev2, next2) = extrapolate(&seq2).unwrap();
+        let (prev3, next3) = extrapolate(&seq3).unwrap();
+
+        assert_eq!((prev1, next1), (-3, 18));
+        assert_eq!((prev2, next2), (0, 28));
+        assert_eq!((prev3, next3), (5, 68));
+    }
+
+    #[test]
+    fn test_correct_puzzle_prediction() {
+        let seq1 = vec![
+            2, 0, -2, -4, -6, -8, -10, -12, -14, -16, -18, -20, -22, -24, -26, -28, -30, -32, -34,
+            -36, -38,
+        ];
+
+        let (prev1, next1) = extrapolate(&seq1).unwrap();
+
+        assert_eq!(prev1, 4);
+        assert_eq!(next1, -40);
+    }
+
+    #[test]
+    fn test_extrapolate_multiple_steps() {
+        let history = History::new(&[1, 3, 6, 10, 15, 21]).unwrap();
+
+        assert_eq!(history.degree(), 2);
+        assert_eq!(history.extrapolate_forward(3), Ok(vec![28, 36, 45]));
+        assert_eq!(history.extrapolate_backward(3), Ok(vec![0, 0, 1]));
+        assert_eq!(
+            extrapolate_steps(&[1, 3, 6, 10, 15, 21], 2),
+            Ok((vec![0, 0], vec![28, 36]))
+        );
+    }
+
+    #[test]
+    fn test_large_values_do_not_overflow() {
+        // n^5 quickly grows past what fits in an i32
+        let nums: Vec<i64> = (1..=100).map(|n: i64| n.pow(5)).collect();
+        let history = History::new(&nums).unwrap();
+
+        assert_eq!(history.degree(), 5);
+        assert_eq!(history.extrapolate_forward(1), Ok(vec![101_i128.pow(5)]));
+        assert_eq!(history.extrapolate_backward(2), Ok(vec![0, -1]));
+    }
+
+    #[test]
+    fn test_recover_polynomial() {
+        let linear = History::new(&[0, 3, 6, 9, 12, 15])
+            .unwrap()
+            .polynomial()
+            .unwrap();
+        let quadratic = History::new(&[1, 3, 6, 10, 15, 21])
+            .unwrap()
+            .polynomial()
+            .unwrap();
+        let cubic = History::new(&[10, 13, 16, 21, 30, 45])
+            .unwrap()
+            .polynomial()
+            .unwrap();
+
+        assert_eq!(linear.to_string(), "3n");
+        assert_eq!(quadratic.to_string(), "1/2n^2 + 3/2n + 1");
+        assert_eq!(cubic.to_string(), "1/3n^3 - n^2 + 11/3n + 10");
+    }
+
+    #[test]
+    fn test_polynomial_matches_prediction() {
+        let seq = vec![10, 13, 16, 21, 30, 45];
+        let polynomial = History::new(&seq).unwrap().polynomial().unwrap();
+
+        for (n, &value) in seq.iter().enumerate() {
+            assert_eq!(
+                polynomial.eval(n as i64),
+                Ok(Rational::from_int(value.into()))
+            );
+        }
+        assert_eq!(
+            polynomial.eval(6),
+            Ok(Rational::from_int(extrapolate(&seq).unwrap().1))
+        );
+        assert_eq!(polynomial.eval(-1), Ok(Rational::from_int(5)));
+    }
+
+    #[test]
+    fn test_polynomial_display() {
+        let constant = History::new(&[-4, -4, -4]).unwrap().polynomial().unwrap();
+        let zero = History::new(&[0, 0, 0]).unwrap().polynomial().unwrap();
+        let negative = History::new(&[2, 0, -2, -4]).unwrap().polynomial().unwrap();
+
+        assert_eq!(constant.to_string(), "-4");
+        assert_eq!(zero.to_string(), "0");
+        assert_eq!(negative.to_string(), "-2n + 2");
+    }
+
+    #[test]
+    fn test_polynomial_overflow_is_reported() {
+        // C(n, 25): the history is small, but the coefficients have 25! as denominator
+        let seq: Vec<i64> = (0..28_i64)
+            .map(|n| (0..25).fold(1, |acc, k| acc * (n - k) / (k + 1)))
+            .collect();
+        let history = History::new(&seq).unwrap();
+
+        assert_eq!(extrapolate(&seq), Ok((-1, 3276)));
+        assert_eq!(
+            history.polynomial().unwrap().eval(26),
+            Ok(Rational::from_int(26))
+        );
+        assert_eq!(
+            history.polynomial().and_then(|p| p.eval(i64::MAX)),
+            Err(HistoryError::Overflow)
+        );
+    }
+
+    #[test]
+    fn test_exponential_sequence_is_unconfirmed() {
+        let powers: Vec<i64> = (0..20).map(|n| 2_i64.pow(n)).collect();
+
+        assert!(!History::new(&powers).unwrap().is_confirmed());
+    }
+
+    #[test]
+    fn test_random_sequence_is_unconfirmed() {
+        let seq = vec![4, -17, 93, 8, 0, 51, -66, 12, 7, 300];
+
+        assert!(!History::new(&seq).unwrap().is_confirmed());
+        assert!(History::new(&seq[..3]).is_ok());
+    }
+
+    #[test]
+    fn test_short_sequences_use_their_last_difference() {
+        assert_eq!(extrapolate(&[1, 4, 9]), Ok((0, 16)));
+        assert_eq!(extrapolate(&[1, 4]), Ok((-2, 7)));
+        assert!(!History::new(&[1, 4, 9]).unwrap().is_confirmed());
+        assert!(History::new(&[1, 4, 9, 16]).unwrap().is_confirmed());
+    }
+
+    #[test]
+    fn test_too_short_sequence_is_rejected() {
+        assert_eq!(extrapolate(&[]), Err(HistoryError::TooShort));
+        assert_eq!(extrapolate(&[7]), Err(HistoryError::TooShort));
+        assert_eq!(extrapolate(&[7, 7]), Ok((7, 7)));
+    }
+
+    #[test]
+    fn test_two_values_are_linear() {
+        assert_eq!(extrapolate(&[1, 4]), Ok((-2, 7)));
+        assert_eq!(extrapolate_steps(&[5, 3], 2), Ok((vec![7, 9], vec![1, -1])));
+    }
+
+    #[test]
+    fn test_overflow_is_reported() {
+        // every row of differences doubles in size, so after 64 rows they're past an i128
+        let seq: Vec<i64> = (0..70)
+            .map(|n| if n % 2 == 0 { i64::MAX } else { i64::MIN })
+            .collect();
+
+        assert_eq!(extrapolate(&seq), Err(HistoryError::Overflow));
+        assert_eq!(
+            History::new(&[0, 1, 8, 27, 64]).unwrap().value_at(i64::MAX),
+            Err(HistoryError::Overflow)
+        );
+    }
+}
@@ -0,0 +1,434 @@
+use std::{
+    fmt, fs, io,
+    path::{Path, PathBuf},
+};
+
+const DEFAULT_BASE_URL: &str = "https://adventofcode.com";
+const DEFAULT_YEAR: u32 = 2023;
+const DEFAULT_CACHE: &str = "./cache";
+const USER_AGENT: &str = "aoc-fetch/0.1 (caches every input, fetches each once)";
+
+/// Where and as whom to fetch from, read from a config file.
+#[derive(Debug, PartialEq)]
+pub struct Config {
+    /// The value of the `session` cookie of a logged in browser.
+    pub session: String,
+    /// The site to fetch from, which is only something else than adventofcode.com in tests.
+    pub base_url: String,
+    pub year: u32,
+    /// The directory that holds a `<year>/day<NN>` directory per fetched day.
+    pub cache: PathBuf,
+}
+
+/// Why a config file couldn't be read, with the number of the offending line.
+#[derive(Debug, PartialEq)]
+pub enum ConfigError {
+    /// The line has no `=`, or nothing before it.
+    Malformed(usize),
+    /// The key isn't one of `session`, `base_url`, `year` and `cache`.
+    UnknownKey(usize),
+    /// The year isn't a number.
+    BadYear(usize),
+    /// There's no `session` line, without which nothing but the puzzle text can be fetched.
+    MissingSession,
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ConfigError::Malformed(line) => write!(f, "line {} isn't like key=value", line),
+            ConfigError::UnknownKey(line) => write!(f, "line {} has an unknown key", line),
+            ConfigError::BadYear(line) => write!(f, "line {} has a year that isn't a number", line),
+            ConfigError::MissingSession => write!(f, "there's no session"),
+        }
+    }
+}
+
+impl Config {
+    /// Parses a config with a `key=value` per line, like `year=2023`. Empty lines and lines
+    /// starting with `#` are skipped, and only `session` has to be there.
+    pub fn parse(contents: &str) -> Result<Config, ConfigError> {
+        let mut session: Option<String> = None;
+        let mut config = Config {
+            session: String::new(),
+            base_url: String::from(DEFAULT_BASE_URL),
+            year: DEFAULT_YEAR,
+            cache: PathBuf::from(DEFAULT_CACHE),
+        };
+
+        for (i, line) in contents.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let (key, value) = line
+                .split_once('=')
+                .filter(|(key, _)| !key.trim().is_empty())
+                .ok_or(ConfigError::Malformed(i + 1))?;
+            let value = value.trim();
+
+            match key.trim() {
+                "session" => session = Some(String::from(value)),
+                "base_url" => config.base_url = String::from(value.trim_end_matches('/')),
+                "year" => config.year = value.parse().map_err(|_| ConfigError::BadYear(i + 1))?,
+                "cache" => config.cache = PathBuf::from(value),
+                _ => return Err(ConfigError::UnknownKey(i + 1)),
+            }
+        }
+
+        config.session = session
+            .filter(|s| !s.is_empty())
+            .ok_or(ConfigError::MissingSession)?;
+
+        Ok(config)
+    }
+
+    /// The directory a day's files are cached in, like `cache/2023/day04`.
+    pub fn day_dir(&self, day: u32) -> PathBuf {
+        self.cache
+            .join(self.year.to_string())
+            .join(format!("day{:02}", day))
+    }
+}
+
+/// Why a day couldn't be fetched.
+#[derive(Debug, PartialEq)]
+pub enum FetchError {
+    /// Puzzles only go from day 1 to day 25.
+    NoSuchDay(u32),
+    /// The site answered, but not with the page, like a 404 for a day that isn't out yet or
+    /// a 400 for a session that expired.
+    Status { url: String, status: u16 },
+    /// The site couldn't be reached at all.
+    Transport { url: String, reason: String },
+    /// A cached file couldn't be read or written.
+    Io { path: PathBuf, reason: String },
+}
+
+impl fmt::Display for FetchError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FetchError::NoSuchDay(day) => write!(f, "there's no day {}", day),
+            FetchError::Status { url, status } => write!(f, "{} answered {}", url, status),
+            FetchError::Transport { url, reason } => write!(f, "{} failed, {}", url, reason),
+            FetchError::Io { path, reason } => write!(f, "{} failed, {}", path.display(), reason),
+        }
+    }
+}
+
+/// Whether a file came from the site or was already in the cache.
+#[derive(Debug, PartialEq)]
+pub enum Source {
+    Downloaded,
+    Cached,
+}
+
+fn io_error(path: &Path) -> impl FnOnce(io::Error) -> FetchError + '_ {
+    move |e| FetchError::Io {
+        path: path.to_path_buf(),
+        reason: e.to_string(),
+    }
+}
+
+fn get(config: &Config, path: &str) -> Result<String, FetchError> {
+    let url = format!("{}{}", config.base_url, path);
+
+    let response = ureq::get(&url)
+        .set("Cookie", &format!("session={}", config.session))
+        .set("User-Agent", USER_AGENT)
+        .call()
+        .map_err(|e| match e {
+            ureq::Error::Status(status, _) => FetchError::Status {
+                url: url.clone(),
+                status,
+            },
+            ureq::Error::Transport(t) => FetchError::Transport {
+                url: url.clone(),
+                reason: t.to_string(),
+            },
+        })?;
+
+    response.into_string().map_err(|e| FetchError::Transport {
+        url,
+        reason: e.to_string(),
+    })
+}
+
+/// Reads a cached file, or downloads it into the cache when it isn't there yet. A file that's
+/// in the cache is never downloaded again, since the site asks not to be hit for the same
+/// thing twice and inputs don't change.
+fn cached(config: &Config, file: &Path, url_path: &str) -> Result<(String, Source), FetchError> {
+    if file.exists() {
+        let contents = fs::read_to_string(file).map_err(io_error(file))?;
+
+        return Ok((contents, Source::Cached));
+    }
+
+    let contents = get(config, url_path)?;
+
+    // Written to the side and then renamed, so an interrupted fetch never leaves a half file
+    // that would count as cached.
+    let partial = file.with_extension("partial");
+    fs::write(&partial, &contents).map_err(io_error(&partial))?;
+    fs::rename(&partial, file).map_err(io_error(file))?;
+
+    Ok((contents, Source::Downloaded))
+}
+
+/// The text of every `<pre><code>` block in a puzzle page, which is where the examples are.
+pub fn examples(page: &str) -> Vec<String> {
+    let mut examples: Vec<String> = Vec::new();
+    let mut rest = page;
+
+    while let Some(start) = rest.find("<pre><code>") {
+        rest = &rest[start + "<pre><code>".len()..];
+        let Some(end) = rest.find("</code></pre>") else {
+            break;
+        };
+
+        examples.push(unescape(&strip_tags(&rest[..end])));
+        rest = &rest[end..];
+    }
+
+    examples
+}
+
+/// Drops the markup inside a block, like the `<em>` around highlighted parts of an example.
+fn strip_tags(html: &str) -> String {
+    let mut text = String::with_capacity(html.len());
+    let mut in_tag = false;
+
+    for c in html.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => in_tag = false,
+            c if !in_tag => text.push(c),
+            _ => (),
+        }
+    }
+
+    text
+}
+
+fn unescape(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&")
+}
+
+/// Fetches a day into its cache directory as
+/// - `puzzle.html`, the puzzle page as it was when first fetched,
+/// - `puzzle.input`, the input of the session's user,
+/// - `example.input`, `example2.input` and so on, one per code block of the page, which are
+///   named the way the days look for them with `--example`.
+///
+/// Returns every file with where it came from.
+pub fn fetch_day(config: &Config, day: u32) -> Result<Vec<(PathBuf, Source)>, FetchError> {
+    if !(1..=25).contains(&day) {
+        return Err(FetchError::NoSuchDay(day));
+    }
+
+    let dir = config.day_dir(day);
+    fs::create_dir_all(&dir).map_err(io_error(&dir))?;
+
+    let mut fetched: Vec<(PathBuf, Source)> = Vec::new();
+    let page_path = format!("/{}/day/{}", config.year, day);
+
+    let input = dir.join("puzzle.input");
+    let (_, source) = cached(config, &input, &format!("{}/input", page_path))?;
+    fetched.push((input, source));
+
+    let page = dir.join("puzzle.html");
+    let (html, source) = cached(config, &page, &page_path)?;
+    fetched.push((page, source));
+
+    for (i, example) in examples(&html).iter().enumerate() {
+        let file = match i {
+            0 => dir.join("example.input"),
+            i => dir.join(format!("example{}.input", i + 1)),
+        };
+
+        // The examples are taken from the cached page, so they're only written when missing.
+        if file.exists() {
+            fetched.push((file, Source::Cached));
+        } else {
+            fs::write(&file, example).map_err(io_error(&file))?;
+            fetched.push((file, Source::Downloaded));
+        }
+    }
+
+    Ok(fetched)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{
+        io::{BufRead, BufReader, Write},
+        net::TcpListener,
+        sync::{Arc, Mutex},
+        thread,
+    };
+
+    const PAGE: &str = "<article><p>For example:</p>\n\
+        <pre><code>Card 1: 41 48 | 83 86\nCard 2: 13 32 | 61 30\n</code></pre>\n\
+        <p>Which is <code>13</code>. And then:</p>\n\
+        <pre><code>a &lt; <em>b</em> &amp;&amp; c\n</code></pre></article>";
+
+    /// A stand-in for the site on a local port, which answers the puzzle page and the input
+    /// of day 4 and a 404 for anything else. Returns its base url and the paths asked for.
+    fn serve() -> (String, Arc<Mutex<Vec<String>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let seen = Arc::clone(&requests);
+
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = stream.unwrap();
+                let mut lines = BufReader::new(&stream).lines().map(|l| l.unwrap());
+
+                let path = lines.next().unwrap().split(' ').nth(1).unwrap().to_string();
+                let cookie = lines
+                    .by_ref()
+                    .take_while(|l| !l.is_empty())
+                    .any(|l| l.eq_ignore_ascii_case("cookie: session=secret"));
+                seen.lock().unwrap().push(path.clone());
+
+                let (status, body) = match path.as_str() {
+                    _ if !cookie => ("400 Bad Request", "Puzzle inputs differ by user."),
+                    "/2023/day/4" => ("200 OK", PAGE),
+                    "/2023/day/4/input" => ("200 OK", "Card 1: 1 | 1\n"),
+                    _ => ("404 Not Found", "Not found"),
+                };
+
+                write!(
+                    stream,
+                    "HTTP/1.1 {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    status,
+                    body.len(),
+                    body
+                )
+                .unwrap();
+            }
+        });
+
+        (base_url, requests)
+    }
+
+    fn config_for(base_url: &str, name: &str) -> Config {
+        let cache = std::env::temp_dir().join(format!("aoc-test-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&cache);
+
+        Config::parse(&format!(
+            "session=secret\nbase_url={}\ncache={}",
+            base_url,
+            cache.display()
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_parse_config() {
+        let config = Config::parse("# token\nsession = abc\n\nyear=2022\ncache=inputs\n").unwrap();
+
+        assert_eq!(config.session, "abc");
+        assert_eq!(config.year, 2022);
+        assert_eq!(config.base_url, "https://adventofcode.com");
+        assert_eq!(config.day_dir(4), PathBuf::from("inputs/2022/day04"));
+
+        assert_eq!(Config::parse("year=2023"), Err(ConfigError::MissingSession));
+        assert_eq!(Config::parse("session"), Err(ConfigError::Malformed(1)));
+        assert_eq!(
+            Config::parse("session=a\nday=1"),
+            Err(ConfigError::UnknownKey(2))
+        );
+        assert_eq!(
+            Config::parse("session=a\nyear=x"),
+            Err(ConfigError::BadYear(2))
+        );
+    }
+
+    #[test]
+    fn test_examples() {
+        assert_eq!(
+            examples(PAGE),
+            vec![
+                "Card 1: 41 48 | 83 86\nCard 2: 13 32 | 61 30\n",
+                "a < b && c\n"
+            ]
+        );
+        assert_eq!(examples("<p>No code here</p>"), Vec::<String>::new());
+    }
+
+    #[test]
+    fn test_fetch_day_caches() {
+        let (base_url, requests) = serve();
+        let config = config_for(&base_url, "caches");
+        let dir = config.day_dir(4);
+
+        let first = fetch_day(&config, 4).unwrap();
+        assert!(first
+            .iter()
+            .all(|(_, source)| *source == Source::Downloaded));
+        assert_eq!(
+            first
+                .iter()
+                .map(|(path, _)| path.clone())
+                .collect::<Vec<_>>(),
+            vec![
+                dir.join("puzzle.input"),
+                dir.join("puzzle.html"),
+                dir.join("example.input"),
+                dir.join("example2.input"),
+            ]
+        );
+        assert_eq!(
+            fs::read_to_string(dir.join("puzzle.input")).unwrap(),
+            "Card 1: 1 | 1\n"
+        );
+        assert_eq!(
+            fs::read_to_string(dir.join("example2.input")).unwrap(),
+            "a < b && c\n"
+        );
+
+        let second = fetch_day(&config, 4).unwrap();
+        assert!(second.iter().all(|(_, source)| *source == Source::Cached));
+        assert_eq!(
+            *requests.lock().unwrap(),
+            vec!["/2023/day/4/input", "/2023/day/4"]
+        );
+
+        fs::remove_dir_all(&config.cache).unwrap();
+    }
+
+    #[test]
+    fn test_fetch_day_errors() {
+        let (base_url, _) = serve();
+        let config = config_for(&base_url, "errors");
+
+        assert_eq!(fetch_day(&config, 26), Err(FetchError::NoSuchDay(26)));
+        assert_eq!(
+            fetch_day(&config, 5),
+            Err(FetchError::Status {
+                url: format!("{}/2023/day/5/input", base_url),
+                status: 404
+            })
+        );
+        assert!(!config.day_dir(5).join("puzzle.input").exists());
+
+        let wrong_session = Config {
+            session: String::from("expired"),
+            ..config_for(&base_url, "errors")
+        };
+        assert!(matches!(
+            fetch_day(&wrong_session, 4),
+            Err(FetchError::Status { status: 400, .. })
+        ));
+
+        fs::remove_dir_all(&config.cache).unwrap();
+    }
+}
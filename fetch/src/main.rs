@@ -0,0 +1,70 @@
+use std::{env, fs};
+
+use fetch::{fetch_day, Config, Source};
+
+const DEFAULT_CONFIG: &str = "./aoc.config";
+
+fn usage() {
+    eprintln!("Usage: aoc-fetch fetch --day N [--config path]");
+}
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+
+    if args.get(1).map(|a| a.as_str()) != Some("fetch") {
+        usage();
+        return;
+    }
+
+    let day: u32 = match args.iter().position(|a| a == "--day") {
+        Some(i) => match args.get(i + 1).and_then(|d| d.parse().ok()) {
+            Some(day) => day,
+            None => {
+                usage();
+                return;
+            }
+        },
+        None => {
+            usage();
+            return;
+        }
+    };
+
+    let config_path = match args.iter().position(|a| a == "--config") {
+        Some(i) => match args.get(i + 1) {
+            Some(path) => path.as_str(),
+            None => {
+                usage();
+                return;
+            }
+        },
+        None => DEFAULT_CONFIG,
+    };
+
+    let config = match fs::read_to_string(config_path) {
+        Ok(contents) => match Config::parse(&contents) {
+            Ok(config) => config,
+            Err(e) => {
+                eprintln!("Can't use config {}, {}", config_path, e);
+                return;
+            }
+        },
+        Err(e) => {
+            eprintln!("Can't read config {}, {}", config_path, e);
+            return;
+        }
+    };
+
+    match fetch_day(&config, day) {
+        Ok(files) => {
+            for (path, source) in files {
+                let source = match source {
+                    Source::Downloaded => "downloaded",
+                    Source::Cached => "cached",
+                };
+                println!("{} ({})", path.display(), source);
+            }
+        }
+        Err(e) => eprintln!("Can't fetch day {}, {}", day, e),
+    }
+}